@@ -1,7 +1,7 @@
 #![no_main]
 sp1_zkvm::entrypoint!(main);
 
-use snark_bn254_verifier::Groth16Verifier;
+use snark_bn254_verifier::{Groth16Verifier, VerificationOutcome};
 use substrate_bn::Fr;
 
 const GROTH16_VK_BYTES: &[u8] = include_bytes!("../../../../.sp1/circuits/v2.0.0/groth16_vk.bin");
@@ -21,11 +21,15 @@ pub fn main() {
     println!("cycle-tracker-end: verify");
 
     match result {
-        Ok(true) => {
+        VerificationOutcome::Valid => {
             println!("Proof is valid");
         }
-        Ok(false) | Err(_) => {
-            println!("Proof is invalid");
+        VerificationOutcome::Invalid { reason } => {
+            println!("Proof is invalid: {}", reason);
+            panic!();
+        }
+        VerificationOutcome::Malformed { error } => {
+            println!("Proof is malformed: {}", error);
             panic!();
         }
     }
@@ -75,13 +75,15 @@ fn save_verification_params(
     raw_proof: &[u8],
     vkey_hash: &[u8],
     committed_values_digest: &[u8],
-    public_inputs: &[String; 2]
+    public_inputs: &[String; 2],
+    circuit_vkey_hash: &[u8; 32]
 ) {
     let params = json!({
         "raw_proof": hex::encode(raw_proof),
         "vkey_hash": hex::encode(vkey_hash),
         "committed_values_digest": hex::encode(committed_values_digest),
         "public_inputs": public_inputs.iter().map(|input| input.to_string()).collect::<Vec<String>>(),
+        "circuit_vkey_hash": hex::encode(circuit_vkey_hash),
     });
 
     let json_file = format!("{}.json", proof_file);
@@ -107,18 +109,26 @@ fn save_params() {
                         panic!("Invalid proof file name. It should contain either 'groth16' or 'plonk'.")
                     };
 
-                    let (raw_proof, public_inputs) = SP1ProofWithPublicValues::load(&proof_file)
+                    let (raw_proof, public_inputs, circuit_vkey_hash) = SP1ProofWithPublicValues::load(&proof_file)
                         .map(|sp1_proof_with_public_values| match proof_mode {
                             ProofMode::Groth16 => {
                                 let proof = sp1_proof_with_public_values
                                     .proof
                                     .try_as_groth_16()
                                     .unwrap();
-                                (hex::decode(proof.raw_proof).unwrap(), proof.public_inputs)
+                                (
+                                    hex::decode(proof.raw_proof).unwrap(),
+                                    proof.public_inputs,
+                                    proof.groth16_vkey_hash,
+                                )
                             }
                             ProofMode::Plonk => {
                                 let proof = sp1_proof_with_public_values.proof.try_as_plonk().unwrap();
-                                (hex::decode(proof.raw_proof).unwrap(), proof.public_inputs)
+                                (
+                                    hex::decode(proof.raw_proof).unwrap(),
+                                    proof.public_inputs,
+                                    proof.plonk_vkey_hash,
+                                )
                             }
                             _ => panic!("Invalid proof mode. Use 'groth16' or 'plonk'."),
                         })
@@ -140,7 +150,8 @@ fn save_params() {
                         &raw_proof, 
                         &vkey_hash,
                         &committed_values_digest,
-                        &public_inputs);
+                        &public_inputs,
+                        &circuit_vkey_hash);
                 }
             }
         }
@@ -267,7 +278,7 @@ mod tests {
 
     use super::*;
 
-    use snark_bn254_verifier::{Groth16Verifier, PlonkVerifier};
+    use snark_bn254_verifier::{Groth16Verifier, PlonkVerifier, VerificationOutcome};
     use strum::IntoEnumIterator;
     use substrate_bn::Fr;
 
@@ -308,21 +319,22 @@ mod tests {
             let committed_values_digest = Fr::from_slice(&committed_values_digest)
                 .expect("Unable to read committed_values_digest");
 
-            let is_valid = match proof_mode {
+            let outcome = match proof_mode {
                 ProofMode::Groth16 => {
                     Groth16Verifier::verify(&raw_proof, &vk, &[vkey_hash, committed_values_digest])
-                        .expect("Groth16 proof is invalid")
                 }
                 ProofMode::Plonk => {
                     PlonkVerifier::verify(&raw_proof, &vk, &[vkey_hash, committed_values_digest])
-                        .expect("Plonk proof is invalid")
                 }
                 _ => panic!("Invalid proof mode. Use 'groth16' or 'plonk'."),
             };
 
-            if !is_valid {
-                panic!("{:?} proof is invalid", proof_mode);
-            }
+            assert_eq!(
+                outcome,
+                VerificationOutcome::Valid,
+                "{:?} proof is not valid",
+                proof_mode
+            );
         }
 
         Elf::iter().for_each(|program| {
@@ -0,0 +1,10 @@
+#!/bin/sh
+# Build the verification core without std for a bare-metal target
+TARGET=thumbv7em-none-eabi
+
+if ! rustup target list --installed | grep -qx "$TARGET"; then
+    echo "The $TARGET target is not installed, add it with: rustup target add $TARGET" >&2
+    exit 1
+fi
+
+cargo build --lib --no-default-features --target "$TARGET"
@@ -1,8 +1,18 @@
+use crate::error::Error;
+
 pub(crate) const GAMMA: &str = "gamma";
 pub(crate) const BETA: &str = "beta";
 pub(crate) const ALPHA: &str = "alpha";
 pub(crate) const ZETA: &str = "zeta";
 
+// Domain separator used by gnark to hash Groth16 commitments into public inputs
+pub(crate) const COMMITMENT_DST: &[u8] = b"bsb22-commitment";
+// Challenge used by gnark to fold Pedersen commitments
+pub(crate) const FOLDING_CHALLENGE: &str = "r";
+// Challenge deriving the coefficients of batch checks when no RNG is available
+#[cfg(not(feature = "rng"))]
+pub(crate) const BATCH_CHALLENGE: &str = "batch";
+
 pub const MASK: u8 = 0b11 << 6;
 pub const COMPRESSED_POSTIVE: u8 = 0b10 << 6;
 pub const COMPRESSED_NEGATIVE: u8 = 0b11 << 6;
@@ -15,13 +25,16 @@ pub enum CompressedPointFlag {
     Infinity = COMPRESSED_INFINITY as isize,
 }
 
-impl From<u8> for CompressedPointFlag {
-    fn from(val: u8) -> Self {
+impl TryFrom<u8> for CompressedPointFlag {
+    type Error = Error;
+
+    // 0b00 is the flag of uncompressed points
+    fn try_from(val: u8) -> Result<Self, Error> {
         match val {
-            COMPRESSED_POSTIVE => CompressedPointFlag::Positive,
-            COMPRESSED_NEGATIVE => CompressedPointFlag::Negative,
-            COMPRESSED_INFINITY => CompressedPointFlag::Infinity,
-            _ => panic!("Invalid compressed point flag"),
+            COMPRESSED_POSTIVE => Ok(CompressedPointFlag::Positive),
+            COMPRESSED_NEGATIVE => Ok(CompressedPointFlag::Negative),
+            COMPRESSED_INFINITY => Ok(CompressedPointFlag::Infinity),
+            _ => Err(Error::UnexpectedFlag),
         }
     }
 }
@@ -1,21 +1,13 @@
-use std::cmp::Ordering;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
 
-use bn::{AffineG1, AffineG2, Fq, Fq2};
+use bn::{arith::U256, AffineG1, AffineG2, Fq, Fq2, Fr, Group, G1, G2};
 
 use crate::{
-    constants::{CompressedPointFlag, MASK},
+    constants::{CompressedPointFlag, COMPRESSED_INFINITY, MASK},
     error::Error,
 };
 
-use crate::wasm_bindgen;
-
-#[wasm_bindgen]
-/// Test
-extern {
-    #[wasm_bindgen(js_namespace = console)]
-    pub fn log(s: &str);
-}
-
 pub fn is_zeroed(first_byte: u8, buf: &[u8]) -> Result<bool, Error> {
     if first_byte != 0 {
         return Ok(false);
@@ -29,30 +21,33 @@ pub fn is_zeroed(first_byte: u8, buf: &[u8]) -> Result<bool, Error> {
     Ok(true)
 }
 
+// Reads the x coordinate and flag of a compressed point, rejecting unknown flags, x beyond the
+// modulus and the point at infinity with any other bit set, so that each point has one encoding
 pub(crate) fn deserialize_with_flags(buf: &[u8]) -> Result<(Fq, CompressedPointFlag), Error> {
     if buf.len() != 32 {
         return Err(Error::InvalidXLength);
     };
 
-    let m_data = buf[0] & MASK;
-    if m_data == u8::from(CompressedPointFlag::Infinity) {
-        if !is_zeroed(buf[0] & !MASK, &buf[1..32]).map_err(|_| Error::InvalidPoint)? {
+    let flag = CompressedPointFlag::try_from(buf[0] & MASK)?;
+    if flag == CompressedPointFlag::Infinity {
+        if !is_zeroed(buf[0] & !MASK, &buf[1..32])? {
             return Err(Error::InvalidPoint);
         }
-        Ok((Fq::zero(), CompressedPointFlag::Infinity))
-    } else {
-        let mut x_bytes: [u8; 32] = [0u8; 32];
-        x_bytes.copy_from_slice(buf);
-        x_bytes[0] &= !MASK;
+        return Ok((Fq::zero(), flag));
+    }
 
-        let x = Fq::from_be_bytes_mod_order(&x_bytes).expect("Failed to convert x bytes to Fq");
+    let mut x_bytes: [u8; 32] = [0u8; 32];
+    x_bytes.copy_from_slice(buf);
+    x_bytes[0] &= !MASK;
 
-        Ok((x, m_data.into()))
-    }
+    Ok((fq_from_be_bytes(&x_bytes)?, flag))
 }
 
 pub(crate) fn compressed_x_to_g1_point(buf: &[u8]) -> Result<AffineG1, Error> {
     let (x, m_data) = deserialize_with_flags(buf)?;
+    if m_data == CompressedPointFlag::Infinity {
+        return Ok(G1::zero().into());
+    }
     let (y, neg_y) = AffineG1::get_ys_from_x_unchecked(x).ok_or(Error::InvalidPoint)?;
 
     let mut final_y = y;
@@ -69,6 +64,9 @@ pub(crate) fn compressed_x_to_g1_point(buf: &[u8]) -> Result<AffineG1, Error> {
 
 pub(crate) fn unchecked_compressed_x_to_g1_point(buf: &[u8]) -> Result<AffineG1, Error> {
     let (x, m_data) = deserialize_with_flags(buf)?;
+    if m_data == CompressedPointFlag::Infinity {
+        return Ok(G1::zero().into());
+    }
     let (y, neg_y) = AffineG1::get_ys_from_x_unchecked(x).ok_or(Error::InvalidPoint)?;
 
     let mut final_y = y;
@@ -90,53 +88,89 @@ pub fn uncompressed_bytes_to_g1_point(buf: &[u8]) -> Result<AffineG1, Error> {
     
     let (x_bytes, y_bytes) = buf.split_at(32);
 
-    let x = Fq::from_slice(x_bytes).map_err(Error::Field)?;
-    let y = Fq::from_slice(y_bytes).map_err(Error::Field)?;
+    let x = fq_from_be_bytes(x_bytes)?;
+    let y = fq_from_be_bytes(y_bytes)?;
     AffineG1::new(x, y).map_err(Error::Group)
 }
 
+/// Same as [`uncompressed_bytes_to_g1_point`], but maps gnark's all-zero encoding of the point at
+/// infinity to the identity instead of rejecting it.
+pub(crate) fn uncompressed_bytes_to_g1_point_or_zero(buf: &[u8]) -> Result<G1, Error> {
+    if buf.len() != 64 {
+        return Err(Error::InvalidXLength);
+    };
+
+    if is_zeroed(buf[0], &buf[1..])? {
+        return Ok(G1::zero());
+    }
+
+    uncompressed_bytes_to_g1_point(buf).map(G1::from)
+}
+
 pub(crate) fn compressed_x_to_g2_point(buf: &[u8]) -> Result<AffineG2, Error> {
     if buf.len() != 64 {
         return Err(Error::InvalidXLength);
     };
 
     let (x1, flag) = deserialize_with_flags(&buf[..32])?;
-    let x0 = Fq::from_be_bytes_mod_order(&buf[32..64]).map_err(Error::Field)?;
+    let x0 = fq_from_be_bytes(&buf[32..64])?;
     let x = Fq2::new(x0, x1);
 
     if flag == CompressedPointFlag::Infinity {
-        return Ok(AffineG2::one());
+        if !is_zeroed(0, &buf[32..64])? {
+            return Err(Error::InvalidPoint);
+        }
+        return Ok(G2::zero().into());
     }
 
-    let (y, neg_y) = AffineG2::get_ys_from_x_unchecked(x).ok_or(Error::InvalidPoint)?;
+    let (y, _) = AffineG2::get_ys_from_x_unchecked(x).ok_or(Error::InvalidPoint)?;
 
-    match flag {
-        CompressedPointFlag::Positive => Ok(AffineG2::new(x, y).map_err(Error::Group)?),
-        CompressedPointFlag::Negative => Ok(AffineG2::new(x, neg_y).map_err(Error::Group)?),
-        _ => Err(Error::InvalidPoint),
-    }
+    AffineG2::new(x, flagged_g2_y(y, flag)?).map_err(Error::Group)
 }
 
 pub(crate) fn unchecked_compressed_x_to_g2_point(buf: &[u8]) -> Result<AffineG2, Error> {
     if buf.len() != 64 {
-        log(&format!("Invalid input buffer length: expected 64, got {}", buf.len()));
         return Err(Error::InvalidXLength);
     };
 
     let (x1, flag) = deserialize_with_flags(&buf[..32])?;
-    let x0 = Fq::from_be_bytes_mod_order(&buf[32..64]).map_err(Error::Field)?;
+    let x0 = fq_from_be_bytes(&buf[32..64])?;
     let x = Fq2::new(x0, x1);
 
     if flag == CompressedPointFlag::Infinity {
-        return Ok(AffineG2::one());
+        if !is_zeroed(0, &buf[32..64])? {
+            return Err(Error::InvalidPoint);
+        }
+        return Ok(G2::zero().into());
     }
 
-    let (y, neg_y) = AffineG2::get_ys_from_x_unchecked(x).ok_or(Error::InvalidPoint)?;
+    let (y, _) = AffineG2::get_ys_from_x_unchecked(x).ok_or(Error::InvalidPoint)?;
+
+    Ok(AffineG2::new_unchecked(x, flagged_g2_y(y, flag)?))
+}
+
+// gnark's `E2.LexicographicallyLargest`: compares y and -y by their imaginary parts, or by their
+// real parts when the imaginary part is zero
+fn is_lexicographically_largest(y: Fq2) -> bool {
+    let neg_y = -y;
+    if y.imaginary().is_zero() {
+        y.real() > neg_y.real()
+    } else {
+        y.imaginary() > neg_y.imaginary()
+    }
+}
 
-    match flag {
-        CompressedPointFlag::Positive => Ok(AffineG2::new_unchecked(x, y)),
-        CompressedPointFlag::Negative => Ok(AffineG2::new_unchecked(x, neg_y)),
-        _ => Err(Error::InvalidPoint),
+// Picks the root `y` or `-y` flagged as the smaller or the larger one
+fn flagged_g2_y(y: Fq2, flag: CompressedPointFlag) -> Result<Fq2, Error> {
+    let largest = match flag {
+        CompressedPointFlag::Positive => false,
+        CompressedPointFlag::Negative => true,
+        CompressedPointFlag::Infinity => return Err(Error::InvalidPoint),
+    };
+    if is_lexicographically_largest(y) == largest {
+        Ok(y)
+    } else {
+        Ok(-y)
     }
 }
 
@@ -149,13 +183,340 @@ pub(crate) fn uncompressed_bytes_to_g2_point(buf: &[u8]) -> Result<AffineG2, Err
     let (x1_bytes, x0_bytes) = x_bytes.split_at(32);
     let (y1_bytes, y0_bytes) = y_bytes.split_at(32);
 
-    let x1 = Fq::from_slice(x1_bytes).map_err(Error::Field)?;
-    let x0 = Fq::from_slice(x0_bytes).map_err(Error::Field)?;
-    let y1 = Fq::from_slice(y1_bytes).map_err(Error::Field)?;
-    let y0 = Fq::from_slice(y0_bytes).map_err(Error::Field)?;
+    let x1 = fq_from_be_bytes(x1_bytes)?;
+    let x0 = fq_from_be_bytes(x0_bytes)?;
+    let y1 = fq_from_be_bytes(y1_bytes)?;
+    let y0 = fq_from_be_bytes(y0_bytes)?;
 
     let x = Fq2::new(x0, x1);
     let y = Fq2::new(y0, y1);
 
     AffineG2::new(x, y).map_err(Error::Group)
 }
+
+pub(crate) fn unchecked_uncompressed_bytes_to_g1_point(buf: &[u8]) -> Result<AffineG1, Error> {
+    if buf.len() != 64 {
+        return Err(Error::InvalidXLength);
+    };
+
+    let (x_bytes, y_bytes) = buf.split_at(32);
+
+    let x = fq_from_be_bytes(x_bytes)?;
+    let y = fq_from_be_bytes(y_bytes)?;
+    Ok(AffineG1::new_unchecked(x, y))
+}
+
+pub(crate) fn unchecked_uncompressed_bytes_to_g2_point(buf: &[u8]) -> Result<AffineG2, Error> {
+    if buf.len() != 128 {
+        return Err(Error::InvalidXLength);
+    }
+
+    // gnark writes the point at infinity as all zeros
+    if is_zeroed(buf[0], &buf[1..])? {
+        return Ok(G2::zero().into());
+    }
+
+    let (x_bytes, y_bytes) = buf.split_at(64);
+    let (x1_bytes, x0_bytes) = x_bytes.split_at(32);
+    let (y1_bytes, y0_bytes) = y_bytes.split_at(32);
+
+    let x1 = fq_from_be_bytes(x1_bytes)?;
+    let x0 = fq_from_be_bytes(x0_bytes)?;
+    let y1 = fq_from_be_bytes(y1_bytes)?;
+    let y0 = fq_from_be_bytes(y0_bytes)?;
+
+    Ok(AffineG2::new_unchecked(Fq2::new(x0, x1), Fq2::new(y0, y1)))
+}
+
+pub(crate) fn g1_to_bytes(g1: &AffineG1) -> Result<Vec<u8>, Error> {
+    // Minimum size of the buffer is 64 bytes
+
+    let mut bytes = Vec::with_capacity(64);
+    bytes.extend_from_slice(&g1.x().0.0.to_bytes_be());
+    bytes.extend_from_slice(&g1.y().0.0.to_bytes_be());
+
+    Ok(bytes)
+}
+
+pub(crate) fn g2_to_bytes(g2: &AffineG2) -> Vec<u8> {
+    // Same layout as gnark: X.A1 | X.A0 | Y.A1 | Y.A0
+    let mut bytes = Vec::with_capacity(128);
+    bytes.extend_from_slice(&g2.x().imaginary().0.0.to_bytes_be());
+    bytes.extend_from_slice(&g2.x().real().0.0.to_bytes_be());
+    bytes.extend_from_slice(&g2.y().imaginary().0.0.to_bytes_be());
+    bytes.extend_from_slice(&g2.y().real().0.0.to_bytes_be());
+
+    bytes
+}
+
+/// Writes a point in gnark's compressed encoding, the inverse of [`compressed_x_to_g1_point`]: `x`
+/// with the top two bits flagging whether `y` is the smaller or the larger of its two roots.
+pub(crate) fn g1_to_compressed_bytes(g1: &AffineG1) -> Result<[u8; 32], Error> {
+    let (y, neg_y) = AffineG1::get_ys_from_x_unchecked(g1.x()).ok_or(Error::InvalidPoint)?;
+    let smaller = if y.cmp(&neg_y) == Ordering::Greater {
+        neg_y
+    } else {
+        y
+    };
+    let flag = if g1.y() == smaller {
+        CompressedPointFlag::Positive
+    } else {
+        CompressedPointFlag::Negative
+    };
+
+    let mut bytes = g1.x().0.0.to_bytes_be();
+    bytes[0] |= u8::from(flag);
+    Ok(bytes)
+}
+
+/// Writes a point in gnark's compressed encoding, the inverse of [`compressed_x_to_g2_point`]:
+/// `x.A1` carrying the flag of `y`, then `x.A0`.
+pub(crate) fn g2_to_compressed_bytes(g2: &AffineG2) -> Result<[u8; 64], Error> {
+    let flag = if is_lexicographically_largest(g2.y()) {
+        CompressedPointFlag::Negative
+    } else {
+        CompressedPointFlag::Positive
+    };
+
+    let mut bytes = [0u8; 64];
+    bytes[..32].copy_from_slice(&g2.x().imaginary().0.0.to_bytes_be());
+    bytes[32..].copy_from_slice(&g2.x().real().0.0.to_bytes_be());
+    bytes[0] |= u8::from(flag);
+    Ok(bytes)
+}
+
+/// How the points of a gnark-serialized verifying key or proof are encoded.
+///
+/// gnark's `WriteTo` compresses points, `WriteRawTo` does not. Loaders detect the encoding from
+/// the flag bits of the first point, and reject points with the other encoding after it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointEncoding {
+    /// `x` only, with the top two bits flagging which `y` it is, or the point at infinity.
+    Compressed,
+    /// `x` and `y`, with the top two bits clear. The point at infinity is all zeros.
+    Uncompressed,
+}
+
+impl PointEncoding {
+    /// Detects the encoding of the point at the start of `buf`. Coordinates are below the base
+    /// field modulus, so the top two bits of an uncompressed point are always clear.
+    pub(crate) fn detect(buf: &[u8]) -> Self {
+        match buf.first() {
+            Some(first_byte) if first_byte & MASK != 0 => PointEncoding::Compressed,
+            _ => PointEncoding::Uncompressed,
+        }
+    }
+
+    pub(crate) fn g1_len(self) -> usize {
+        match self {
+            PointEncoding::Compressed => 32,
+            PointEncoding::Uncompressed => 64,
+        }
+    }
+
+    pub(crate) fn g2_len(self) -> usize {
+        2 * self.g1_len()
+    }
+
+    // A point with the other encoding would be read with the wrong length and misparse everything
+    // after it
+    fn check(self, buf: &[u8]) -> Result<(), Error> {
+        if PointEncoding::detect(buf) != self {
+            return Err(Error::InconsistentPointEncoding { expected: self });
+        }
+        Ok(())
+    }
+
+    // The point at infinity is only written by gnark where it is a legitimate value
+    pub(crate) fn is_identity(self, buf: &[u8]) -> bool {
+        match self {
+            PointEncoding::Compressed => {
+                buf[0] == COMPRESSED_INFINITY && buf[1..].iter().all(|b| *b == 0)
+            }
+            PointEncoding::Uncompressed => buf.iter().all(|b| *b == 0),
+        }
+    }
+
+    fn check_finite(self, buf: &[u8]) -> Result<(), Error> {
+        self.check(buf)?;
+        if self.is_identity(buf) {
+            return Err(Error::UnexpectedIdentity);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn read_g1(self, buf: &[u8]) -> Result<AffineG1, Error> {
+        self.check_finite(buf)?;
+        match self {
+            PointEncoding::Compressed => compressed_x_to_g1_point(buf),
+            PointEncoding::Uncompressed => uncompressed_bytes_to_g1_point(buf),
+        }
+    }
+
+    pub(crate) fn read_g1_or_zero(self, buf: &[u8]) -> Result<G1, Error> {
+        self.check(buf)?;
+        match self {
+            PointEncoding::Compressed => match deserialize_with_flags(buf)? {
+                (_, CompressedPointFlag::Infinity) => Ok(G1::zero()),
+                _ => compressed_x_to_g1_point(buf).map(G1::from),
+            },
+            PointEncoding::Uncompressed => uncompressed_bytes_to_g1_point_or_zero(buf),
+        }
+    }
+
+    pub(crate) fn read_g2(self, buf: &[u8]) -> Result<AffineG2, Error> {
+        self.check_finite(buf)?;
+        match self {
+            PointEncoding::Compressed => compressed_x_to_g2_point(buf),
+            PointEncoding::Uncompressed => uncompressed_bytes_to_g2_point(buf),
+        }
+    }
+
+    // Reads a point that must not be the point at infinity, such as a generator or a trapdoor
+    // commitment. Trusted keys still map the point at infinity to the identity.
+    pub(crate) fn read_key_g1(
+        self,
+        buf: &[u8],
+        validation: KeyValidation,
+    ) -> Result<AffineG1, Error> {
+        match validation {
+            KeyValidation::Strict => self.read_g1(buf),
+            KeyValidation::Trusted => {
+                self.check(buf)?;
+                if self.is_identity(buf) {
+                    return Ok(G1::zero().into());
+                }
+                match self {
+                    PointEncoding::Compressed => unchecked_compressed_x_to_g1_point(buf),
+                    PointEncoding::Uncompressed => unchecked_uncompressed_bytes_to_g1_point(buf),
+                }
+            }
+        }
+    }
+
+    // Reads a point gnark legitimately leaves at infinity, such as the selector of a gate the
+    // circuit does not use or the K point of an unconstrained public input
+    pub(crate) fn read_key_g1_or_zero(
+        self,
+        buf: &[u8],
+        validation: KeyValidation,
+    ) -> Result<AffineG1, Error> {
+        self.check(buf)?;
+        if self.is_identity(buf) {
+            return Ok(G1::zero().into());
+        }
+        self.read_key_g1(buf, validation)
+    }
+
+    pub(crate) fn read_key_g2(
+        self,
+        buf: &[u8],
+        validation: KeyValidation,
+    ) -> Result<AffineG2, Error> {
+        match validation {
+            KeyValidation::Strict => self.read_g2(buf),
+            KeyValidation::Trusted => {
+                self.check(buf)?;
+                match self {
+                    PointEncoding::Compressed => unchecked_compressed_x_to_g2_point(buf),
+                    PointEncoding::Uncompressed => unchecked_uncompressed_bytes_to_g2_point(buf),
+                }
+            }
+        }
+    }
+
+    pub(crate) fn write_g1(self, bytes: &mut Vec<u8>, g1: &AffineG1) -> Result<(), Error> {
+        self.write_g1_or_zero(bytes, &G1::from(*g1))
+    }
+
+    pub(crate) fn write_g1_or_zero(self, bytes: &mut Vec<u8>, g1: &G1) -> Result<(), Error> {
+        match (AffineG1::from_jacobian(*g1), self) {
+            (Some(g1), PointEncoding::Compressed) => {
+                bytes.extend_from_slice(&g1_to_compressed_bytes(&g1)?)
+            }
+            (Some(g1), PointEncoding::Uncompressed) => bytes.extend_from_slice(&g1_to_bytes(&g1)?),
+            (None, PointEncoding::Compressed) => {
+                bytes.push(COMPRESSED_INFINITY);
+                bytes.extend_from_slice(&[0u8; 31]);
+            }
+            (None, PointEncoding::Uncompressed) => bytes.extend_from_slice(&[0u8; 64]),
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write_g2(self, bytes: &mut Vec<u8>, g2: &AffineG2) -> Result<(), Error> {
+        match (G2::from(*g2).is_zero(), self) {
+            (false, PointEncoding::Compressed) => {
+                bytes.extend_from_slice(&g2_to_compressed_bytes(g2)?)
+            }
+            (false, PointEncoding::Uncompressed) => bytes.extend_from_slice(&g2_to_bytes(g2)),
+            (true, PointEncoding::Compressed) => {
+                bytes.push(COMPRESSED_INFINITY);
+                bytes.extend_from_slice(&[0u8; 63]);
+            }
+            (true, PointEncoding::Uncompressed) => bytes.extend_from_slice(&[0u8; 128]),
+        }
+        Ok(())
+    }
+}
+
+/// How the points of a verifying key are validated when it is loaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyValidation {
+    /// Check that every point is on the curve and in the prime-order subgroup, and that the
+    /// generators and the α, β, γ and δ points are not the point at infinity. The default for keys
+    /// supplied at runtime.
+    #[default]
+    Strict,
+    /// Skip the checks, for keys from a trusted source such as the SP1 circuit keys embedded in
+    /// this crate.
+    Trusted,
+}
+
+/// Encodes public inputs as the `uint256[]` public input argument of the gnark Solidity verifiers:
+/// 32-byte big-endian words, without the ABI length prefix.
+pub fn solidity_public_inputs(public_inputs: &[Fr]) -> Vec<u8> {
+    public_inputs
+        .iter()
+        .flat_map(|input| input.into_u256().to_bytes_be())
+        .collect()
+}
+
+/// Reads a base field element from its 32-byte big-endian encoding, rejecting values beyond the
+/// modulus.
+pub(crate) fn fq_from_be_bytes(bytes: &[u8]) -> Result<Fq, Error> {
+    if bytes.len() != 32 {
+        return Err(Error::InvalidData);
+    }
+    let value = U256::from_slice(bytes).map_err(|_| Error::InvalidData)?;
+    Fq::from_u256(value).map_err(|_| Error::BeyondTheModulus)
+}
+
+/// Reads a scalar from its 32-byte big-endian encoding, rejecting values beyond the modulus.
+pub(crate) fn fr_from_be_bytes(bytes: &[u8]) -> Result<Fr, Error> {
+    if bytes.len() != 32 {
+        return Err(Error::InvalidData);
+    }
+    // Unlike `Fr::from_slice`, which reduces values beyond the modulus
+    let value = U256::from_slice(bytes).map_err(|_| Error::InvalidData)?;
+    Fr::new(value).ok_or(Error::BeyondTheModulus)
+}
+
+/// Parses a scalar from a decimal string, or from a `0x`-prefixed big-endian hex string of up to
+/// 32 bytes.
+pub(crate) fn fr_from_str(s: &str) -> Result<Fr, Error> {
+    match s.strip_prefix("0x") {
+        Some(digits) => {
+            if digits.is_empty() || digits.len() > 64 {
+                return Err(Error::InvalidData);
+            }
+            let mut padded = [b'0'; 64];
+            padded[64 - digits.len()..].copy_from_slice(digits.as_bytes());
+            let mut bytes = [0u8; 32];
+            hex::decode_to_slice(padded, &mut bytes).map_err(|_| Error::InvalidData)?;
+            fr_from_be_bytes(&bytes)
+        }
+        None => Fr::from_str(s).ok_or(Error::InvalidData),
+    }
+}
+
@@ -1,6 +1,9 @@
+use alloc::boxed::Box;
 use bn::{CurveError, FieldError, GroupError};
 use thiserror::Error;
 
+use crate::converter::PointEncoding;
+
 #[derive(Error, Debug)]
 pub enum Error {
     // Cryptographic Errors
@@ -16,6 +19,8 @@ pub enum Error {
     PairingCheckFailed,
     #[error("Invalid point in subgroup check")]
     InvalidPoint,
+    #[error("Unexpected point at infinity")]
+    UnexpectedIdentity,
 
     // Mathematical Errors
     #[error("Beyond the modulus")]
@@ -40,6 +45,27 @@ pub enum Error {
     UnexpectedFlag,
     #[error("Invalid data")]
     InvalidData,
+    #[error("Inconsistent point encoding: expected {expected:?} points")]
+    InconsistentPointEncoding { expected: PointEncoding },
+    #[error(
+        "Insufficient buffer length reading {field} at offset {offset}: expected at least {expected} bytes, got {got}"
+    )]
+    InsufficientBufferLength {
+        field: &'static str,
+        offset: usize,
+        expected: usize,
+        got: usize,
+    },
+    #[error("Invalid {field} at offset {offset}")]
+    InvalidField { field: &'static str, offset: usize },
+    #[error("Invalid {field} at offset {offset}: {source}")]
+    InvalidFieldEncoding {
+        field: &'static str,
+        offset: usize,
+        source: Box<Error>,
+    },
+    #[error("Trailing bytes after {field} at offset {offset}")]
+    TrailingBytes { field: &'static str, offset: usize },
 
     // Conversion Errors
     #[error("Failed to get Fr from random bytes")]
@@ -1,102 +1,121 @@
-use bn::AffineG1;
+use alloc::{vec, vec::Vec};
+use bn::{AffineG1, AffineG2, Group, G1, G2};
 
 use crate::{
-    converter::{
-        unchecked_compressed_x_to_g1_point, unchecked_compressed_x_to_g2_point,
-        uncompressed_bytes_to_g1_point, uncompressed_bytes_to_g2_point,
-    },
+    converter::{g1_to_bytes, g2_to_bytes, KeyValidation, PointEncoding},
+    error::Error,
     groth16::{Groth16G1, Groth16G2, Groth16Proof, Groth16VerifyingKey, PedersenVerifyingKey},
+    reader::Reader,
 };
 
 use super::error::Groth16Error;
-use crate::wasm_bindgen;
-
-#[wasm_bindgen]
-/// Test
-extern {
-    #[wasm_bindgen(js_namespace = console)]
-    pub fn log(s: &str);
-}
 
+// Reads a proof written by gnark's `WriteRawTo` or `WriteTo`, with uncompressed or compressed points
 pub(crate) fn load_groth16_proof_from_bytes(buffer: &[u8]) -> Result<Groth16Proof, Groth16Error> {
+    let mut reader = Reader::new(buffer, PointEncoding::detect(buffer));
+
+    let ar = reader.read_g1("Ar")?;
+    let bs = reader.read_g2("Bs")?;
+    let krs = reader.read_g1("Krs")?;
 
-    if buffer.len() < 256 {
-        return Err(Groth16Error::PrepareInputsFailed);
+    // gnark always writes the commitment section, empty for circuits without commitments
+    let num_commitments = reader.read_len("number of commitments")?;
+    let mut commitments = Vec::new();
+    for _ in 0..num_commitments {
+        commitments.push(reader.read_g1("commitment")?);
     }
 
-    let ar = uncompressed_bytes_to_g1_point(&buffer[..64])?;
-    let bs = uncompressed_bytes_to_g2_point(&buffer[64..192])?;
-    let krs = uncompressed_bytes_to_g1_point(&buffer[192..256])?;
+    let commitment_pok = reader.read_g1_or_zero("commitment proof of knowledge")?;
+    reader.finish("Groth16 proof")?;
 
     Ok(Groth16Proof {
         ar,
         bs,
         krs,
-        commitments: Vec::new(),
-        commitment_pok: AffineG1::one(),
+        commitments,
+        commitment_pok,
     })
+}
+
+// Loads a proof encoded for the gnark Solidity verifier, in the layout written by `groth16_proof_to_solidity_bytes`
+pub(crate) fn load_groth16_proof_from_solidity_bytes(
+    buffer: &[u8],
+) -> Result<Groth16Proof, Groth16Error> {
+    let mut reader = Reader::new(buffer, PointEncoding::Uncompressed);
+
+    let ar = reader.read_g1("Ar")?;
+    let bs = reader.read_g2("Bs")?;
+    let krs = reader.read_g1("Krs")?;
+
+    let (commitments, commitment_pok) = if reader.is_empty() {
+        (Vec::new(), G1::zero())
+    } else {
+        let commitment = reader.read_g1("commitment")?;
+        let commitment_pok = reader.read_g1("commitment proof of knowledge")?;
+        (vec![commitment], commitment_pok.into())
+    };
+    reader.finish("Solidity proof")?;
 
+    Ok(Groth16Proof {
+        ar,
+        bs,
+        krs,
+        commitments,
+        commitment_pok,
+    })
 }
 
+// Reads a verifying key written by gnark's `WriteTo` or `WriteRawTo`, with compressed or
+// uncompressed points
 pub(crate) fn load_groth16_verifying_key_from_bytes(
     buffer: &[u8],
+    validation: KeyValidation,
 ) -> Result<Groth16VerifyingKey, Groth16Error> {
+    let encoding = PointEncoding::detect(buffer);
+    let mut reader = Reader::new(buffer, encoding);
 
-    if buffer.len() < 292 {
-        log(&format!("Insufficient buffer length: expected at least 292 bytes, got {}", buffer.len()));
-        return Err(Groth16Error::PrepareInputsFailed);
-    }
-
-    let g1_alpha = unchecked_compressed_x_to_g1_point(&buffer[..32])?;
-    let g1_beta = unchecked_compressed_x_to_g1_point(&buffer[32..64])?;
-    let g2_beta = unchecked_compressed_x_to_g2_point(&buffer[64..128])?;
-    let g2_gamma = unchecked_compressed_x_to_g2_point(&buffer[128..192])?;
-    let g1_delta = unchecked_compressed_x_to_g1_point(&buffer[192..224])?;
-    let g2_delta = unchecked_compressed_x_to_g2_point(&buffer[224..288])?;
+    let g1_alpha = reader.read_key_g1("[α]₁", validation)?;
+    let g1_beta = reader.read_key_g1("[β]₁", validation)?;
+    let g2_beta = reader.read_key_g2("[β]₂", validation)?;
+    let g2_gamma = reader.read_key_g2("[γ]₂", validation)?;
+    let g1_delta = reader.read_key_g1("[δ]₁", validation)?;
+    let g2_delta = reader.read_key_g2("[δ]₂", validation)?;
 
-    let num_k = u32::from_be_bytes([buffer[288], buffer[289], buffer[290], buffer[291]]);
+    let num_k = reader.read_len("number of K points")?;
     let mut k = Vec::new();
-    let mut offset = 292;
-
-    // Check if buffer has enough bytes for all k points
-    if buffer.len() < (offset + 32 * num_k as usize) {
-        log(&format!("Insufficient buffer length: expected at least {} bytes, got {}", offset + 32 * num_k as usize, buffer.len()));
-        return Err(Groth16Error::PrepareInputsFailed);
-    }
-
     for _ in 0..num_k {
-        let point = unchecked_compressed_x_to_g1_point(&buffer[offset..offset + 32])?;
-        k.push(point);
-        offset += 32;
+        k.push(reader.read_key_g1_or_zero("K point", validation)?);
     }
 
-    let num_of_array_of_public_and_commitment_committed = u32::from_be_bytes([
-        buffer[offset],
-        buffer[offset + 1],
-        buffer[offset + 2],
-        buffer[offset + 3],
-    ]);
-    offset += 4;
+    // Each committed index is written by gnark as a uint64
+    let num_of_array_of_public_and_commitment_committed =
+        reader.read_len("number of committed public input arrays")?;
+    let mut public_and_commitment_committed = Vec::new();
     for _ in 0..num_of_array_of_public_and_commitment_committed {
-        let num = u32::from_be_bytes([
-            buffer[offset],
-            buffer[offset + 1],
-            buffer[offset + 2],
-            buffer[offset + 3],
-        ]);
-        offset += 4;
+        let num = reader.read_len("number of committed public inputs")?;
+        let mut committed = Vec::new();
         for _ in 0..num {
-            offset += 4;
+            let field = "committed public input index";
+            let offset = reader.offset();
+            let index = reader.read_u64(field)?;
+            let index = u32::try_from(index).map_err(|_| Error::InvalidField { field, offset })?;
+            committed.push(index);
         }
+        public_and_commitment_committed.push(committed);
     }
 
-    if buffer.len() < (offset + 128) {
-        log(&format!("Insufficient buffer length: expected at least {} bytes, got {}", offset + 128, buffer.len()));
-        return Err(Groth16Error::PrepareInputsFailed);
-    }
-    let commitment_key_g = unchecked_compressed_x_to_g2_point(&buffer[offset..offset + 64])?;
-    let commitment_key_g_root_sigma_neg =
-        unchecked_compressed_x_to_g2_point(&buffer[offset + 64..offset + 128])?;
+    // gnark leaves the Pedersen key of circuits without commitments at infinity, where it is never
+    // used
+    let mut read_commitment_key = |field| -> Result<AffineG2, Error> {
+        let buf = reader.read_bytes(encoding.g2_len(), field)?;
+        if public_and_commitment_committed.is_empty() && encoding.is_identity(buf) {
+            return Ok(G2::zero().into());
+        }
+        encoding.read_key_g2(buf, validation)
+    };
+    let commitment_key_g = read_commitment_key("commitment key G")?;
+    let commitment_key_g_root_sigma_neg = read_commitment_key("commitment key G^(-σ)")?;
+    reader.finish("Groth16 verifying key")?;
 
     Ok(Groth16VerifyingKey {
         g1: Groth16G1 {
@@ -114,6 +133,86 @@ pub(crate) fn load_groth16_verifying_key_from_bytes(
             g: commitment_key_g,
             g_root_sigma_neg: commitment_key_g_root_sigma_neg,
         },
-        public_and_commitment_committed: vec![vec![0u32; 0]],
+        public_and_commitment_committed,
     })
 }
+
+// Inverse of `load_groth16_proof_from_bytes`. Like gnark, the commitment section is always written, with the
+// point at infinity as proof of knowledge when there are no commitments.
+pub(crate) fn groth16_proof_to_bytes(
+    proof: &Groth16Proof,
+    encoding: PointEncoding,
+) -> Result<Vec<u8>, Groth16Error> {
+    let g1_len = encoding.g1_len();
+    let mut bytes = Vec::with_capacity(4 * g1_len + 4 + g1_len * (proof.commitments.len() + 1));
+    encoding.write_g1(&mut bytes, &proof.ar)?;
+    encoding.write_g2(&mut bytes, &proof.bs)?;
+    encoding.write_g1(&mut bytes, &proof.krs)?;
+
+    bytes.extend_from_slice(&(proof.commitments.len() as u32).to_be_bytes());
+    for commitment in &proof.commitments {
+        encoding.write_g1(&mut bytes, commitment)?;
+    }
+    encoding.write_g1_or_zero(&mut bytes, &proof.commitment_pok)?;
+
+    Ok(bytes)
+}
+
+// Inverse of `load_groth16_verifying_key_from_bytes`. gnark stores [β]₁ and [β]₂ as they are, and the loader
+// negates them, so they are negated back.
+pub(crate) fn groth16_verifying_key_to_bytes(
+    vk: &Groth16VerifyingKey,
+    encoding: PointEncoding,
+) -> Result<Vec<u8>, Groth16Error> {
+    let (g1_len, g2_len) = (encoding.g1_len(), encoding.g2_len());
+    let mut bytes = Vec::with_capacity(3 * g1_len + 5 * g2_len + 8 + g1_len * vk.g1.k.len());
+    encoding.write_g1(&mut bytes, &vk.g1.alpha)?;
+    encoding.write_g1(&mut bytes, &-vk.g1.beta)?;
+    encoding.write_g2(&mut bytes, &-vk.g2.beta)?;
+    encoding.write_g2(&mut bytes, &vk.g2.gamma)?;
+    encoding.write_g1(&mut bytes, &vk.g1.delta)?;
+    encoding.write_g2(&mut bytes, &vk.g2.delta)?;
+
+    bytes.extend_from_slice(&(vk.g1.k.len() as u32).to_be_bytes());
+    for k in &vk.g1.k {
+        encoding.write_g1(&mut bytes, k)?;
+    }
+
+    bytes.extend_from_slice(&(vk.public_and_commitment_committed.len() as u32).to_be_bytes());
+    for committed in &vk.public_and_commitment_committed {
+        bytes.extend_from_slice(&(committed.len() as u32).to_be_bytes());
+        for index in committed {
+            bytes.extend_from_slice(&u64::from(*index).to_be_bytes());
+        }
+    }
+
+    encoding.write_g2(&mut bytes, &vk.commitment_key.g)?;
+    encoding.write_g2(&mut bytes, &vk.commitment_key.g_root_sigma_neg)?;
+
+    Ok(bytes)
+}
+
+// Encode a proof for the gnark Solidity verifier: Ar, Bs and Krs as uint256[8], with the G2 coordinates imaginary
+// part first, followed for circuits with a commitment by the commitment and its proof of knowledge. The verifier
+// gnark exports only takes a single commitment.
+pub(crate) fn groth16_proof_to_solidity_bytes(
+    proof: &Groth16Proof,
+) -> Result<Vec<u8>, Groth16Error> {
+    if proof.commitments.len() > 1 {
+        return Err(Groth16Error::InvalidNumberOfCommitments);
+    }
+
+    let mut bytes = Vec::with_capacity(256 + 128 * proof.commitments.len());
+    bytes.extend_from_slice(&g1_to_bytes(&proof.ar)?);
+    bytes.extend_from_slice(&g2_to_bytes(&proof.bs));
+    bytes.extend_from_slice(&g1_to_bytes(&proof.krs)?);
+
+    if let [commitment] = proof.commitments.as_slice() {
+        bytes.extend_from_slice(&g1_to_bytes(commitment)?);
+        let commitment_pok = AffineG1::from_jacobian(proof.commitment_pok)
+            .ok_or(Groth16Error::UnexpectedIdentity)?;
+        bytes.extend_from_slice(&g1_to_bytes(&commitment_pok)?);
+    }
+
+    Ok(bytes)
+}
@@ -1,3 +1,4 @@
+use alloc::string::String;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -8,8 +9,14 @@ pub enum Groth16Error {
     ProcessVerifyingKeyFailed,
     #[error("Prepare inputs failed")]
     PrepareInputsFailed,
+    #[error("Commitment proof of knowledge verification failed")]
+    CommitmentPokVerificationFailed,
+    #[error("Invalid number of commitments")]
+    InvalidNumberOfCommitments,
     #[error("Unexpected identity")]
     UnexpectedIdentity,
-    #[error("General error")]
+    #[error("Invalid snarkjs JSON: {0}")]
+    InvalidSnarkjsJson(String),
+    #[error("General error: {0}")]
     GeneralError(#[from] crate::error::Error),
 }
@@ -1,6 +1,11 @@
 mod converter;
 pub(crate) mod error;
+mod snarkjs;
 mod verify;
 
-pub(crate) use converter::{load_groth16_proof_from_bytes, load_groth16_verifying_key_from_bytes};
+pub use verify::{
+    prepare_verifying_key, snarkjs_public_inputs, Groth16Proof, Groth16VerifyingKey,
+    PreparedVerifyingKey,
+};
+
 pub(crate) use verify::*;
@@ -0,0 +1,211 @@
+use alloc::{format, string::String, vec::Vec};
+use bn::{AffineG1, AffineG2, Fq, Fq2, Fr, Group, G1, G2};
+use serde_json::Value;
+
+use crate::{converter::fr_from_str, error::Error};
+
+use super::{
+    error::Groth16Error, Groth16G1, Groth16G2, Groth16Proof, Groth16VerifyingKey,
+    PedersenVerifyingKey,
+};
+
+// snarkjs writes field elements as decimal strings and points in projective coordinates, with G2
+// coordinates as [c0, c1] pairs: G1 = [x, y, "1"], G2 = [[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]].
+
+pub(crate) fn load_groth16_verifying_key_from_snarkjs_json(
+    json: &str,
+) -> Result<Groth16VerifyingKey, Groth16Error> {
+    let value = parse_json(json)?;
+    check_protocol(&value)?;
+
+    let alpha = g1_field(&value, "vk_alpha_1")?;
+    let beta = g2_field(&value, "vk_beta_2")?;
+    let gamma = g2_field(&value, "vk_gamma_2")?;
+    let delta = g2_field(&value, "vk_delta_2")?;
+
+    let k = field(&value, "IC")?
+        .as_array()
+        .ok_or_else(|| invalid("IC must be an array of G1 points"))?
+        .iter()
+        .map(parse_g1)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let n_public = field(&value, "nPublic")?
+        .as_u64()
+        .ok_or_else(|| invalid("nPublic must be an integer"))?;
+    if k.len() as u64 != n_public + 1 {
+        return Err(invalid(format!(
+            "IC has {} points for {} public inputs",
+            k.len(),
+            n_public
+        )));
+    }
+
+    let g1 = AffineG1::from_jacobian(G1::one()).ok_or(Groth16Error::UnexpectedIdentity)?;
+
+    Ok(Groth16VerifyingKey {
+        // [β]₁ and [δ]₁ are not part of snarkjs keys, and not used by the verifier
+        g1: Groth16G1 {
+            alpha,
+            beta: g1,
+            delta: g1,
+            k,
+        },
+        // gnark keys store -[β]₂, snarkjs keys store [β]₂
+        g2: Groth16G2 {
+            beta: -beta,
+            gamma,
+            delta,
+        },
+        // circom circuits have no BSB22 commitments, so the Pedersen key is left at infinity, as
+        // gnark does for circuits without commitments
+        commitment_key: PedersenVerifyingKey {
+            g: G2::zero().into(),
+            g_root_sigma_neg: G2::zero().into(),
+        },
+        public_and_commitment_committed: Vec::new(),
+    })
+}
+
+pub(crate) fn load_groth16_proof_from_snarkjs_json(
+    json: &str,
+) -> Result<Groth16Proof, Groth16Error> {
+    let value = parse_json(json)?;
+    check_protocol(&value)?;
+
+    Ok(Groth16Proof {
+        ar: g1_field(&value, "pi_a")?,
+        bs: g2_field(&value, "pi_b")?,
+        krs: g1_field(&value, "pi_c")?,
+        commitments: Vec::new(),
+        commitment_pok: G1::zero(),
+    })
+}
+
+pub(crate) fn load_public_inputs_from_snarkjs_json(json: &str) -> Result<Vec<Fr>, Groth16Error> {
+    parse_json(json)?
+        .as_array()
+        .ok_or_else(|| invalid("public signals must be an array of decimal strings"))?
+        .iter()
+        .enumerate()
+        .map(|(i, signal)| {
+            signal
+                .as_str()
+                .ok_or_else(|| invalid(format!("public signal {} must be a string", i)))
+                .and_then(|s| fr_from_str(s).map_err(Groth16Error::from))
+        })
+        .collect()
+}
+
+fn parse_json(json: &str) -> Result<Value, Groth16Error> {
+    serde_json::from_str(json).map_err(|e| invalid(format!("{}", e)))
+}
+
+fn invalid(message: impl Into<String>) -> Groth16Error {
+    Groth16Error::InvalidSnarkjsJson(message.into())
+}
+
+// Files from other proving systems or curves are rejected rather than failing the pairing check
+fn check_protocol(value: &Value) -> Result<(), Groth16Error> {
+    for (name, expected) in [("protocol", "groth16"), ("curve", "bn128")] {
+        match value.get(name).map(Value::as_str) {
+            None => {}
+            Some(Some(found)) if found == expected => {}
+            Some(found) => {
+                return Err(invalid(format!(
+                    "expected {} {}, got {}",
+                    name,
+                    expected,
+                    found.unwrap_or("a non-string value")
+                )))
+            }
+        }
+    }
+    Ok(())
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Result<&'a Value, Groth16Error> {
+    value
+        .get(name)
+        .ok_or_else(|| invalid(format!("missing {}", name)))
+}
+
+fn g1_field(value: &Value, name: &str) -> Result<AffineG1, Groth16Error> {
+    parse_g1(field(value, name)?).map_err(|e| in_field(name, e))
+}
+
+fn g2_field(value: &Value, name: &str) -> Result<AffineG2, Groth16Error> {
+    parse_g2(field(value, name)?).map_err(|e| in_field(name, e))
+}
+
+fn in_field(name: &str, e: Groth16Error) -> Groth16Error {
+    match e {
+        Groth16Error::InvalidSnarkjsJson(message) => invalid(format!("{}: {}", name, message)),
+        e => invalid(format!("{}: {}", name, e)),
+    }
+}
+
+// Only affine points (z = 1) are accepted, which is how snarkjs exports them
+fn coordinates(value: &Value) -> Result<&[Value], Groth16Error> {
+    match value.as_array().map(Vec::as_slice) {
+        Some(coordinates @ [_, _]) | Some(coordinates @ [_, _, _]) => Ok(coordinates),
+        _ => Err(invalid("expected a point as [x, y] or [x, y, z]")),
+    }
+}
+
+fn parse_g1(value: &Value) -> Result<AffineG1, Groth16Error> {
+    let coordinates = coordinates(value)?;
+    if let Some(z) = coordinates.get(2) {
+        if parse_fq(z)? != Fq::one() {
+            return Err(invalid("expected an affine point with z = 1"));
+        }
+    }
+
+    let x = parse_fq(&coordinates[0])?;
+    let y = parse_fq(&coordinates[1])?;
+    Ok(AffineG1::new(x, y).map_err(Error::Group)?)
+}
+
+fn parse_g2(value: &Value) -> Result<AffineG2, Groth16Error> {
+    let coordinates = coordinates(value)?;
+    if let Some(z) = coordinates.get(2) {
+        if parse_fq2(z)? != Fq2::one() {
+            return Err(invalid("expected an affine point with z = 1"));
+        }
+    }
+
+    let x = parse_fq2(&coordinates[0])?;
+    let y = parse_fq2(&coordinates[1])?;
+    Ok(AffineG2::new(x, y).map_err(Error::Group)?)
+}
+
+fn parse_fq2(value: &Value) -> Result<Fq2, Groth16Error> {
+    match value.as_array().map(Vec::as_slice) {
+        Some([c0, c1]) => Ok(Fq2::new(parse_fq(c0)?, parse_fq(c1)?)),
+        _ => Err(invalid("expected an Fq2 element as [c0, c1]")),
+    }
+}
+
+// Coordinates must be canonical: decimal strings below the base field modulus
+fn parse_fq(value: &Value) -> Result<Fq, Groth16Error> {
+    let s = value
+        .as_str()
+        .filter(|s| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit()))
+        .ok_or_else(|| invalid("expected a coordinate as a decimal string"))?;
+
+    // Big-endian base 256 accumulator, overflowing 256 bits is out of range anyway
+    let mut bytes = [0u8; 32];
+    for digit in s.bytes() {
+        let mut carry = u16::from(digit - b'0');
+        for byte in bytes.iter_mut().rev() {
+            let acc = u16::from(*byte) * 10 + carry;
+            *byte = acc as u8;
+            carry = acc >> 8;
+        }
+        if carry != 0 {
+            return Err(Error::BeyondTheModulus.into());
+        }
+    }
+
+    Fq::from_slice(&bytes).map_err(|_| Error::BeyondTheModulus.into())
+}
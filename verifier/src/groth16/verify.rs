@@ -1,77 +1,453 @@
-use bn::{pairing, pairing_batch, AffineG1, AffineG2, Fr, Gt, G1, G2};
+use alloc::{string::ToString, vec, vec::Vec};
+use bn::{pairing, pairing_batch, AffineG1, AffineG2, Fr, Group, Gt, G1, G2};
+use core::hash::Hasher;
 
-use super::error::Groth16Error;
+use crate::{
+    constants::{COMMITMENT_DST, FOLDING_CHALLENGE},
+    converter::{g1_to_bytes, g2_to_bytes, KeyValidation, PointEncoding},
+    error::Error,
+    hash_to_field::WrappedHashToField,
+    outcome::VerificationOutcome,
+    transcript::{batch_coefficients, Transcript},
+};
 
-#[derive(Clone, PartialEq)]
-pub struct Groth16G1 {
-    pub alpha: AffineG1,
-    pub beta: AffineG1,
-    pub delta: AffineG1,
-    pub k: Vec<AffineG1>,
+use super::{
+    converter::{
+        groth16_proof_to_bytes, groth16_proof_to_solidity_bytes, groth16_verifying_key_to_bytes,
+        load_groth16_proof_from_bytes, load_groth16_proof_from_solidity_bytes,
+        load_groth16_verifying_key_from_bytes,
+    },
+    error::Groth16Error,
+    snarkjs::{
+        load_groth16_proof_from_snarkjs_json, load_groth16_verifying_key_from_snarkjs_json,
+        load_public_inputs_from_snarkjs_json,
+    },
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Groth16G1 {
+    pub(crate) alpha: AffineG1,
+    pub(crate) beta: AffineG1,
+    pub(crate) delta: AffineG1,
+    pub(crate) k: Vec<AffineG1>,
 }
 
-#[derive(Clone, PartialEq)]
-pub struct Groth16G2 {
-    pub beta: AffineG2,
-    pub delta: AffineG2,
-    pub gamma: AffineG2,
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Groth16G2 {
+    pub(crate) beta: AffineG2,
+    pub(crate) delta: AffineG2,
+    pub(crate) gamma: AffineG2,
 }
 
-#[derive(Clone, PartialEq)]
-pub struct PedersenVerifyingKey {
-    pub g: AffineG2,
-    pub g_root_sigma_neg: AffineG2,
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PedersenVerifyingKey {
+    pub(crate) g: AffineG2,
+    pub(crate) g_root_sigma_neg: AffineG2,
 }
 
-#[derive(Clone, PartialEq)]
+/// A Groth16 verifying key, as serialized by gnark.
+#[derive(Clone, Debug, PartialEq)]
 pub struct Groth16VerifyingKey {
-    pub g1: Groth16G1,
-    pub g2: Groth16G2,
-    pub commitment_key: PedersenVerifyingKey,
-    pub public_and_commitment_committed: Vec<Vec<u32>>,
+    pub(crate) g1: Groth16G1,
+    pub(crate) g2: Groth16G2,
+    pub(crate) commitment_key: PedersenVerifyingKey,
+    pub(crate) public_and_commitment_committed: Vec<Vec<u32>>,
+}
+
+impl Groth16VerifyingKey {
+    /// Loads a verifying key from its gnark binary encoding, with compressed or uncompressed
+    /// points, checking that every point is valid.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, Groth16Error> {
+        load_groth16_verifying_key_from_bytes(buffer, KeyValidation::Strict)
+    }
+
+    /// Same as [`Self::from_bytes`], with the given validation of the points. Only skip it for
+    /// trusted keys.
+    pub fn from_bytes_with_validation(
+        buffer: &[u8],
+        validation: KeyValidation,
+    ) -> Result<Self, Groth16Error> {
+        load_groth16_verifying_key_from_bytes(buffer, validation)
+    }
+
+    /// Writes the verifying key in the gnark binary encoding read by [`Self::from_bytes`], with
+    /// compressed points.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Groth16Error> {
+        groth16_verifying_key_to_bytes(self, PointEncoding::Compressed)
+    }
+
+    /// Writes the verifying key with the given point encoding, as gnark's `WriteTo` or
+    /// `WriteRawTo` do.
+    pub fn to_bytes_with_encoding(&self, encoding: PointEncoding) -> Result<Vec<u8>, Groth16Error> {
+        groth16_verifying_key_to_bytes(self, encoding)
+    }
+
+    /// Loads a verifying key from the `verification_key.json` exported by snarkjs for a circom
+    /// circuit.
+    pub fn from_snarkjs_json(json: &str) -> Result<Self, Groth16Error> {
+        load_groth16_verifying_key_from_snarkjs_json(json)
+    }
 }
 
-#[allow(dead_code)]
+/// A Groth16 proof, as serialized by gnark.
+#[derive(Clone, Debug)]
 pub struct Groth16Proof {
-    pub ar: AffineG1,
-    pub krs: AffineG1,
-    pub bs: AffineG2,
-    pub commitments: Vec<AffineG1>,
-    pub commitment_pok: AffineG1,
+    pub(crate) ar: AffineG1,
+    pub(crate) krs: AffineG1,
+    pub(crate) bs: AffineG2,
+    pub(crate) commitments: Vec<AffineG1>,
+    pub(crate) commitment_pok: G1,
+}
+
+impl Groth16Proof {
+    /// Loads a proof from its gnark binary encoding, with uncompressed or compressed points.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, Groth16Error> {
+        load_groth16_proof_from_bytes(buffer)
+    }
+
+    /// Writes the proof in the gnark binary encoding read by [`Self::from_bytes`], with
+    /// uncompressed points and the commitment section always present.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Groth16Error> {
+        groth16_proof_to_bytes(self, PointEncoding::Uncompressed)
+    }
+
+    /// Writes the proof with the given point encoding, as gnark's `WriteTo` or `WriteRawTo` do.
+    pub fn to_bytes_with_encoding(&self, encoding: PointEncoding) -> Result<Vec<u8>, Groth16Error> {
+        groth16_proof_to_bytes(self, encoding)
+    }
+
+    /// Loads a proof from the `proof.json` written by snarkjs for a circom circuit.
+    pub fn from_snarkjs_json(json: &str) -> Result<Self, Groth16Error> {
+        load_groth16_proof_from_snarkjs_json(json)
+    }
+
+    /// Loads a proof in the encoding of the gnark Solidity verifier, as submitted onchain: the
+    /// `uint256[8]` proof argument, followed for circuits with a commitment by the commitment and
+    /// its proof of knowledge.
+    pub fn from_solidity_bytes(buffer: &[u8]) -> Result<Self, Groth16Error> {
+        load_groth16_proof_from_solidity_bytes(buffer)
+    }
+
+    /// Encodes the proof as the `uint256[8]` proof argument of the gnark Solidity verifier, followed
+    /// by the `uint256[2]` commitment and proof of knowledge arguments for circuits with a
+    /// commitment. Proofs with several commitments cannot be encoded.
+    ///
+    /// The points are written as they are, with G2 coordinates imaginary part first: the Solidity
+    /// verifier negates them itself.
+    pub fn to_solidity_bytes(&self) -> Result<Vec<u8>, Groth16Error> {
+        groth16_proof_to_solidity_bytes(self)
+    }
+}
+
+/// Reads the public signals from the `public.json` written by snarkjs, in the order of the
+/// verifying key `IC` points.
+pub fn snarkjs_public_inputs(json: &str) -> Result<Vec<Fr>, Groth16Error> {
+    load_public_inputs_from_snarkjs_json(json)
 }
 
-#[derive(Clone, PartialEq)]
+/// A Groth16 verifying key with the proof-independent parts of the pairing check precomputed.
+///
+/// Build it once with [`prepare_verifying_key`] and reuse it across proofs for the same circuit.
+#[derive(Clone, Debug, PartialEq)]
 pub struct PreparedVerifyingKey {
-    pub vk: Groth16VerifyingKey,
-    pub alpha_g1_beta_g2: Gt,
-    pub gamma_g2_neg_pc: G2,
-    pub delta_g2_neg_pc: G2,
+    pub(crate) vk: Groth16VerifyingKey,
+    pub(crate) alpha_g1_beta_g2: Gt,
+    pub(crate) gamma_g2_neg_pc: G2,
+    pub(crate) delta_g2_neg_pc: G2,
+}
+
+/// Precomputes `e(α, β)`, `-[γ]₂` and `-[δ]₂` for a verifying key.
+pub fn prepare_verifying_key(vk: Groth16VerifyingKey) -> PreparedVerifyingKey {
+    // The verifying key stores -β, so e(α, -β_vk) = e(α, β)
+    let alpha_g1_beta_g2 = pairing(vk.g1.alpha.into(), -Into::<G2>::into(vk.g2.beta));
+    let gamma_g2_neg_pc = -Into::<G2>::into(vk.g2.gamma);
+    let delta_g2_neg_pc = -Into::<G2>::into(vk.g2.delta);
+
+    PreparedVerifyingKey {
+        vk,
+        alpha_g1_beta_g2,
+        gamma_g2_neg_pc,
+        delta_g2_neg_pc,
+    }
+}
+
+impl From<Groth16VerifyingKey> for PreparedVerifyingKey {
+    fn from(vk: Groth16VerifyingKey) -> Self {
+        prepare_verifying_key(vk)
+    }
 }
 
 // Prepare the inputs for the Groth16 verification by combining the public inputs with the corresponding elements of the verification key.
-fn prepare_inputs(vk: Groth16VerifyingKey, public_inputs: &[Fr]) -> Result<G1, Groth16Error> {
+fn prepare_inputs(
+    vk: &Groth16VerifyingKey,
+    public_inputs: &[Fr],
+    commitments: &[AffineG1],
+) -> Result<G1, Groth16Error> {
     if (public_inputs.len() + 1) != vk.g1.k.len() {
         return Err(Groth16Error::PrepareInputsFailed);
     }
 
-    Ok(public_inputs
+    let prepared_inputs: G1 = public_inputs
         .iter()
         .zip(vk.g1.k.iter().skip(1))
         .fold(vk.g1.k[0], |acc, (i, b)| acc + (*b * *i))
-        .into())
+        .into();
+
+    // The commitments themselves are part of the public input combination
+    Ok(commitments
+        .iter()
+        .fold(prepared_inputs, |acc, c| acc + G1::from(*c)))
 }
 
-pub fn verify_groth16(
+// Derive the public inputs bound to each BSB22 commitment, as gnark does when solving the commitment wire:
+// hash_to_field(commitment || committed public inputs) with the "bsb22-commitment" domain separator.
+fn compute_commitment_hashes(
     vk: &Groth16VerifyingKey,
     proof: &Groth16Proof,
-    public_inputs: &[Fr],
+    public_inputs: &mut Vec<Fr>,
+) -> Result<Vec<Fr>, Groth16Error> {
+    let mut hash_to_field = WrappedHashToField::new(COMMITMENT_DST)?;
+    let mut hashes = Vec::with_capacity(proof.commitments.len());
+
+    for (commitment, committed) in proof
+        .commitments
+        .iter()
+        .zip(vk.public_and_commitment_committed.iter())
+    {
+        hash_to_field.write(&g1_to_bytes(commitment)?);
+        for index in committed {
+            // Committed indexes are wire indexes, where wire 0 is the constant one
+            let input = (*index as usize)
+                .checked_sub(1)
+                .and_then(|i| public_inputs.get(i))
+                .ok_or(Groth16Error::PrepareInputsFailed)?;
+            hash_to_field.write(&input.into_u256().to_bytes_be());
+        }
+        let hash_bts = hash_to_field.sum()?;
+        hash_to_field.reset();
+
+        let hashed_cmt = Fr::from_bytes_be_mod_order(&hash_bts)
+            .map_err(|_| Error::FailedToGetFrFromRandomBytes)?;
+        public_inputs.push(hashed_cmt);
+        hashes.push(hashed_cmt);
+    }
+
+    Ok(hashes)
+}
+
+// Fold the commitments into a single one with powers of a Fiat-Shamir challenge derived from their hashes.
+fn fold_commitments(commitments: &[AffineG1], hashes: &[Fr]) -> Result<AffineG1, Groth16Error> {
+    if commitments.len() == 1 {
+        return Ok(commitments[0]);
+    }
+
+    let mut transcript = Transcript::new(Some([FOLDING_CHALLENGE.to_string()].to_vec()))?;
+    let mut seed = Vec::with_capacity(hashes.len() * 32);
+    for hash in hashes {
+        seed.extend_from_slice(&hash.into_u256().to_bytes_be());
+    }
+    transcript.bind(FOLDING_CHALLENGE, &seed)?;
+    let challenge = transcript.compute_challenge(FOLDING_CHALLENGE)?;
+    let r = Fr::from_bytes_be_mod_order(&challenge).map_err(Error::Field)?;
+
+    let mut coefficients = Vec::with_capacity(commitments.len());
+    let mut ri = Fr::one();
+    for _ in commitments {
+        coefficients.push(ri);
+        ri *= r;
+    }
+
+    Ok(AffineG1::msm(commitments, &coefficients))
+}
+
+// Check the proof of knowledge of the commitments against the Pedersen verifying key:
+// e(folded commitment, [-σ]G₂) * e(PoK, G₂) == 1
+fn verify_commitment_pok(
+    vk: &PedersenVerifyingKey,
+    proof: &Groth16Proof,
+    hashes: &[Fr],
 ) -> Result<bool, Groth16Error> {
-    let alpha_g1_beta_g2 = pairing(vk.g1.alpha.into(), vk.g2.beta.into());
-    let prepared_inputs = prepare_inputs(vk.clone(), public_inputs)?;
+    if proof.commitments.is_empty() {
+        return Ok(proof.commitment_pok.is_zero());
+    }
+
+    let folded = fold_commitments(&proof.commitments, hashes)?;
 
     Ok(pairing_batch(&[
+        (folded.into(), vk.g_root_sigma_neg.into()),
+        (proof.commitment_pok, vk.g.into()),
+    ])
+    .is_one())
+}
+
+/// Verifies a Groth16 proof
+///
+/// # Arguments
+///
+/// * `vk` - The verifying key
+/// * `proof` - The Groth16 proof
+/// * `public_inputs` - The public inputs to the circuit
+///
+/// # Returns
+///
+/// * `VerificationOutcome` - `Valid` if the proof verifies, `Invalid` if a pairing check fails, or
+///   `Malformed` if the proof does not match the verifying key or public inputs
+pub fn verify_groth16(
+    vk: &Groth16VerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[Fr],
+) -> VerificationOutcome {
+    verify_groth16_prepared(&prepare_verifying_key(vk.clone()), proof, public_inputs)
+}
+
+/// Verifies a Groth16 proof against a prepared verifying key
+///
+/// Same as [`verify_groth16`], without recomputing the verifying key precomputation.
+pub fn verify_groth16_prepared(
+    pvk: &PreparedVerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[Fr],
+) -> VerificationOutcome {
+    match verify_groth16_algebraic(pvk, proof, public_inputs) {
+        Ok(()) => VerificationOutcome::Valid,
+        Err(e) => e.into(),
+    }
+}
+
+fn verify_groth16_algebraic(
+    pvk: &PreparedVerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[Fr],
+) -> Result<(), Groth16Error> {
+    let prepared_inputs = prepare_proof(pvk, proof, public_inputs)?;
+
+    // e(A, B) * e(Σx.[Kvk(t)]₁, -[γ]₂) * e(C, -[δ]₂) == e(α, β)
+    if pairing_batch(&[
         (proof.ar.into(), proof.bs.into()),
-        (prepared_inputs, vk.g2.gamma.into()),
-        (proof.krs.into(), -Into::<G2>::into(vk.g2.delta)),
-    ]) == alpha_g1_beta_g2)
+        (prepared_inputs, pvk.gamma_g2_neg_pc),
+        (proof.krs.into(), pvk.delta_g2_neg_pc),
+    ]) != pvk.alpha_g1_beta_g2
+    {
+        return Err(Groth16Error::ProofVerificationFailed);
+    }
+
+    Ok(())
+}
+
+// Run every check of a proof that does not involve the final pairing, and return the combination of its public
+// inputs with the verifying key.
+fn prepare_proof(
+    pvk: &PreparedVerifyingKey,
+    proof: &Groth16Proof,
+    public_inputs: &[Fr],
+) -> Result<G1, Groth16Error> {
+    let vk = &pvk.vk;
+    if proof.commitments.len() != vk.public_and_commitment_committed.len() {
+        return Err(Groth16Error::InvalidNumberOfCommitments);
+    }
+
+    let mut public_inputs = public_inputs.to_vec();
+    let commitment_hashes = compute_commitment_hashes(vk, proof, &mut public_inputs)?;
+
+    if !verify_commitment_pok(&vk.commitment_key, proof, &commitment_hashes)? {
+        return Err(Groth16Error::CommitmentPokVerificationFailed);
+    }
+
+    prepare_inputs(vk, &public_inputs, &proof.commitments)
+}
+
+/// Verifies several Groth16 proofs against the same prepared verifying key
+///
+/// The proofs are combined with random coefficients rᵢ (see the `rng` feature) into a single pairing check of
+/// n + 2 pairs:
+/// `Πe(rᵢ.Aᵢ, Bᵢ) * e(Σrᵢ.Lᵢ, -[γ]₂) * e(Σrᵢ.Cᵢ, -[δ]₂) == e(α, β)^Σrᵢ`
+///
+/// If the combined check fails, each proof is checked on its own so the bad ones can be reported.
+///
+/// # Arguments
+///
+/// * `pvk` - The prepared verifying key
+/// * `proofs` - The proofs, each with its public inputs
+///
+/// # Returns
+///
+/// * `Vec<VerificationOutcome>` - The outcome of each proof, in the order they were given
+pub fn verify_groth16_batch(
+    pvk: &PreparedVerifyingKey,
+    proofs: &[(&Groth16Proof, &[Fr])],
+) -> Vec<VerificationOutcome> {
+    let mut outcomes = vec![VerificationOutcome::Valid; proofs.len()];
+
+    // Proofs failing the checks outside of the final pairing are reported directly and left out of the batch
+    let mut batch = Vec::with_capacity(proofs.len());
+    for (i, (proof, public_inputs)) in proofs.iter().enumerate() {
+        match prepare_proof(pvk, proof, public_inputs) {
+            Ok(prepared_inputs) => batch.push((i, prepared_inputs)),
+            Err(e) => outcomes[i] = e.into(),
+        }
+    }
+
+    if batch.is_empty() {
+        return outcomes;
+    }
+
+    let coefficients = batch_coefficients(batch.len(), || {
+        let mut data = Vec::with_capacity(batch.len() * (64 + 128 + 64 + 64));
+        for (i, prepared_inputs) in batch.iter() {
+            let proof = proofs[*i].0;
+            data.extend_from_slice(&g1_to_bytes(&proof.ar)?);
+            data.extend_from_slice(&g2_to_bytes(&proof.bs));
+            data.extend_from_slice(&g1_to_bytes(&proof.krs)?);
+            data.extend_from_slice(&g1_to_bytes(&AffineG1::from(*prepared_inputs))?);
+        }
+        Ok(data)
+    });
+
+    if let Ok(coefficients) = coefficients {
+        if check_batch(pvk, proofs, &batch, &coefficients) {
+            return outcomes;
+        }
+    }
+
+    for (i, _) in batch {
+        let (proof, public_inputs) = proofs[i];
+        outcomes[i] = verify_groth16_prepared(pvk, proof, public_inputs);
+    }
+
+    outcomes
+}
+
+// Πe(rᵢ.Aᵢ, Bᵢ) * e(Σrᵢ.Lᵢ, -[γ]₂) * e(Σrᵢ.Cᵢ, -[δ]₂) == e(α, β)^Σrᵢ
+fn check_batch(
+    pvk: &PreparedVerifyingKey,
+    proofs: &[(&Groth16Proof, &[Fr])],
+    batch: &[(usize, G1)],
+    coefficients: &[Fr],
+) -> bool {
+    let mut pairs = Vec::with_capacity(batch.len() + 2);
+    let mut sum_inputs = G1::zero();
+    let mut sum_krs = G1::zero();
+    let mut sum_r = Fr::zero();
+    for ((i, prepared_inputs), r) in batch.iter().zip(coefficients.iter().copied()) {
+        let proof = proofs[*i].0;
+
+        pairs.push((G1::from(proof.ar) * r, G2::from(proof.bs)));
+        sum_inputs = sum_inputs + *prepared_inputs * r;
+        sum_krs = sum_krs + G1::from(proof.krs) * r;
+        sum_r += r;
+    }
+    pairs.push((sum_inputs, pvk.gamma_g2_neg_pc));
+    pairs.push((sum_krs, pvk.delta_g2_neg_pc));
+
+    pairing_batch(&pairs) == pvk.alpha_g1_beta_g2.pow(sum_r)
+}
+
+impl From<Groth16Error> for VerificationOutcome {
+    fn from(error: Groth16Error) -> Self {
+        match error {
+            Groth16Error::ProofVerificationFailed
+            | Groth16Error::CommitmentPokVerificationFailed => VerificationOutcome::invalid(error),
+            _ => VerificationOutcome::malformed(error),
+        }
+    }
 }
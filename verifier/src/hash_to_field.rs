@@ -1,3 +1,4 @@
+use alloc::{vec, vec::Vec};
 use core::hash::Hasher;
 use sha2::Digest;
 
@@ -0,0 +1,357 @@
+//! KZG polynomial commitments over BN254, as implemented by gnark.
+//!
+//! Supports single openings, batch openings of several polynomials at the same point, and batch
+//! openings at different points, against a [`KZGVerifyingKey`] derived from the SRS.
+use alloc::{string::ToString, vec, vec::Vec};
+use bn::{pairing_batch, AffineG1, AffineG2, Fr, G1, G2};
+
+use crate::{
+    constants::GAMMA,
+    converter::{g1_to_bytes, KeyValidation, PointEncoding},
+    error::Error,
+    reader::Reader,
+    transcript::{batch_coefficients, Transcript},
+};
+
+/// A commitment to a polynomial
+pub type Digest = AffineG1;
+
+// Number of Miller loop steps gnark precomputes lines for on BN254
+const NB_LINES: usize = 66;
+
+// Size of the precomputed lines that may follow the points: for each of the two G₂ points, two
+// lines of two E2 coefficients per Miller loop step
+const LINES_LEN: usize = 2 * 2 * NB_LINES * 128;
+
+/// The verifying key of a KZG SRS: `[G₁, G₂, [α]G₂]`
+///
+/// gnark stores precomputed pairing lines for G₂ and `[α]G₂` after the points. bn computes the
+/// lines itself, so they are skipped when reading a key and written back as zeros, as in the SP1
+/// circuit keys.
+#[derive(Clone, Debug)]
+pub struct KZGVerifyingKey {
+    pub(crate) g2: [G2; 2], // [G₂, [α]G₂]
+    pub(crate) g1: G1,
+}
+
+/// An opening of a single polynomial at a point
+#[derive(Clone, Copy, Debug)]
+pub struct OpeningProof {
+    /// Commitment to the quotient polynomial
+    pub h: AffineG1,
+    /// Claimed evaluation of the polynomial at the point
+    pub claimed_value: Fr,
+}
+
+/// An opening of several polynomials at the same point
+#[derive(Clone, Debug)]
+pub struct BatchOpeningProof {
+    /// Commitment to the quotient polynomial of the folded polynomials
+    pub h: AffineG1,
+    /// Claimed evaluations of each polynomial at the point
+    pub claimed_values: Vec<Fr>,
+}
+
+impl KZGVerifyingKey {
+    /// Builds a verifying key from the G₁ and G₂ generators of the SRS and `[α]G₂`.
+    pub fn new(g1: AffineG1, g2: AffineG2, alpha_g2: AffineG2) -> Self {
+        KZGVerifyingKey {
+            g2: [g2.into(), alpha_g2.into()],
+            g1: g1.into(),
+        }
+    }
+
+    /// Loads a verifying key serialized by gnark-crypto's `kzg.VerifyingKey.WriteTo`: G₂, `[α]G₂`
+    /// and G₁, compressed or not, optionally followed by the precomputed pairing lines, which are
+    /// skipped. The points are checked to be valid.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, Error> {
+        Self::from_bytes_with_validation(buffer, KeyValidation::Strict)
+    }
+
+    /// Same as [`Self::from_bytes`], with the given validation of the points.
+    pub fn from_bytes_with_validation(
+        buffer: &[u8],
+        validation: KeyValidation,
+    ) -> Result<Self, Error> {
+        let mut reader = Reader::new(buffer, PointEncoding::detect(buffer));
+        let g2 = reader.read_key_g2("KZG G₂", validation)?;
+        let alpha_g2 = reader.read_key_g2("KZG [α]G₂", validation)?;
+        let g1 = reader.read_key_g1("KZG G₁", validation)?;
+        if !reader.is_empty() {
+            reader.read_bytes(LINES_LEN, "KZG pairing lines")?;
+        }
+        reader.finish("KZG verifying key")?;
+
+        Ok(Self::new(g1, g2, alpha_g2))
+    }
+
+    // Reads a verifying key embedded in a gnark PlonK verifying key, at the position of the reader:
+    // G₁, G₂ and [α]G₂, followed by the lines
+    pub(crate) fn read(reader: &mut Reader, validation: KeyValidation) -> Result<Self, Error> {
+        let g1 = reader.read_key_g1("KZG G₁", validation)?;
+        let g2 = reader.read_key_g2("KZG G₂", validation)?;
+        let alpha_g2 = reader.read_key_g2("KZG [α]G₂", validation)?;
+        reader.read_bytes(LINES_LEN, "KZG pairing lines")?;
+
+        Ok(Self::new(g1, g2, alpha_g2))
+    }
+
+    /// Writes the verifying key as gnark-crypto does: compressed G₂, `[α]G₂` and G₁, followed by
+    /// all-zero pairing lines.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        self.to_bytes_with_encoding(PointEncoding::Compressed)
+    }
+
+    /// Same as [`Self::to_bytes`], with the given point encoding.
+    pub fn to_bytes_with_encoding(&self, encoding: PointEncoding) -> Result<Vec<u8>, Error> {
+        let (g1, g2, alpha_g2) = self.affine_points()?;
+
+        let mut bytes = Vec::with_capacity(points_len(encoding) + LINES_LEN);
+        encoding.write_g2(&mut bytes, &g2)?;
+        encoding.write_g2(&mut bytes, &alpha_g2)?;
+        encoding.write_g1(&mut bytes, &g1)?;
+        bytes.resize(bytes.len() + LINES_LEN, 0);
+
+        Ok(bytes)
+    }
+
+    // Inverse of `read`
+    pub(crate) fn write(&self, bytes: &mut Vec<u8>, encoding: PointEncoding) -> Result<(), Error> {
+        let (g1, g2, alpha_g2) = self.affine_points()?;
+
+        encoding.write_g1(bytes, &g1)?;
+        encoding.write_g2(bytes, &g2)?;
+        encoding.write_g2(bytes, &alpha_g2)?;
+        bytes.resize(bytes.len() + LINES_LEN, 0);
+
+        Ok(())
+    }
+
+    fn affine_points(&self) -> Result<(AffineG1, AffineG2, AffineG2), Error> {
+        let g1 = AffineG1::from_jacobian(self.g1).ok_or(Error::InvalidPoint)?;
+        let g2 = AffineG2::from_jacobian(self.g2[0]).ok_or(Error::InvalidPoint)?;
+        let alpha_g2 = AffineG2::from_jacobian(self.g2[1]).ok_or(Error::InvalidPoint)?;
+        Ok((g1, g2, alpha_g2))
+    }
+}
+
+// Size of [G₁, G₂, [α]G₂]
+fn points_len(encoding: PointEncoding) -> usize {
+    encoding.g1_len() + 2 * encoding.g2_len()
+}
+
+fn derive_gamma(
+    point: &Fr,
+    digests: &[Digest],
+    claimed_values: &[Fr],
+    data_transcript: Option<Vec<u8>>,
+) -> Result<Fr, Error> {
+    let mut transcript = Transcript::new(Some([GAMMA.to_string()].to_vec()))?;
+    transcript.bind(GAMMA, &point.into_u256().to_bytes_be())?;
+
+    for digest in digests.iter() {
+        transcript.bind(GAMMA, &g1_to_bytes(digest)?)?;
+    }
+
+    for claimed_value in claimed_values.iter() {
+        transcript.bind(GAMMA, &claimed_value.into_u256().to_bytes_be())?;
+    }
+
+    if let Some(data_transcript) = data_transcript {
+        transcript.bind(GAMMA, &data_transcript)?;
+    }
+
+    let gamma_byte = transcript.compute_challenge(GAMMA)?;
+    let x = Fr::from_bytes_be_mod_order(gamma_byte.as_slice()).map_err(Error::Field)?;
+
+    Ok(x)
+}
+
+fn fold(di: &[Digest], fai: &[Fr], ci: &[Fr]) -> (AffineG1, Fr) {
+    let mut folded_evaluations = Fr::zero();
+
+    for (fa, c) in fai.iter().zip(ci.iter()) {
+        folded_evaluations += *fa * *c;
+    }
+
+    let folded_digests = AffineG1::msm(di, ci);
+
+    (folded_digests, folded_evaluations)
+}
+
+/// Verifies the opening of a single polynomial at a point
+///
+/// Checks `e([f(α)]G₁ - [f(z)]G₁ + z[H(α)]G₁, G₂) * e(-[H(α)]G₁, [α]G₂) == 1`
+///
+/// # Arguments
+///
+/// * `commitment` - The commitment to the polynomial
+/// * `proof` - The opening proof
+/// * `point` - The point of evaluation
+/// * `vk` - The verifying key
+pub fn verify(
+    commitment: &Digest,
+    proof: &OpeningProof,
+    point: &Fr,
+    vk: &KZGVerifyingKey,
+) -> Result<(), Error> {
+    // [f(α) - f(z) + z*H(α)]G₁
+    let total_g1 = G1::from(*commitment) - vk.g1 * proof.claimed_value + G1::from(proof.h) * *point;
+
+    let pairing_result = pairing_batch(&[(total_g1, vk.g2[0]), (-G1::from(proof.h), vk.g2[1])]);
+
+    if !pairing_result.is_one() {
+        return Err(Error::PairingCheckFailed);
+    }
+
+    Ok(())
+}
+
+/// Folds an opening of several polynomials at the same point into the opening of a single one
+///
+/// # Arguments
+///
+/// * `digests` - The commitments to the polynomials
+/// * `batch_opening_proof` - The batch opening proof
+/// * `point` - The point of evaluation
+/// * `data_transcript` - Extra data bound to the folding challenge
+///
+/// # Returns
+///
+/// * The folded opening proof and the folded commitment
+pub fn fold_proof(
+    digests: &[Digest],
+    batch_opening_proof: &BatchOpeningProof,
+    point: &Fr,
+    data_transcript: Option<Vec<u8>>,
+) -> Result<(OpeningProof, AffineG1), Error> {
+    let nb_digests = digests.len();
+
+    if nb_digests == 0 || nb_digests != batch_opening_proof.claimed_values.len() {
+        return Err(Error::InvalidNumberOfDigests);
+    }
+
+    let gamma = derive_gamma(
+        point,
+        digests,
+        &batch_opening_proof.claimed_values,
+        data_transcript,
+    )?;
+
+    let mut gammai = vec![Fr::zero(); nb_digests];
+    gammai[0] = Fr::one();
+
+    if nb_digests > 1 {
+        gammai[1] = gamma;
+    }
+
+    for i in 2..nb_digests {
+        gammai[i] = gammai[i - 1] * gamma;
+    }
+
+    let (folded_digests, folded_evaluations) =
+        fold(digests, &batch_opening_proof.claimed_values, &gammai);
+
+    let open_proof = OpeningProof {
+        h: batch_opening_proof.h,
+        claimed_value: folded_evaluations,
+    };
+
+    Ok((open_proof, folded_digests))
+}
+
+/// Verifies the opening of several polynomials at the same point
+///
+/// # Arguments
+///
+/// * `digests` - The commitments to the polynomials
+/// * `batch_opening_proof` - The batch opening proof
+/// * `point` - The point of evaluation
+/// * `data_transcript` - Extra data bound to the folding challenge
+/// * `vk` - The verifying key
+pub fn batch_verify_single_point(
+    digests: &[Digest],
+    batch_opening_proof: &BatchOpeningProof,
+    point: &Fr,
+    data_transcript: Option<Vec<u8>>,
+    vk: &KZGVerifyingKey,
+) -> Result<(), Error> {
+    let (folded_proof, folded_digest) =
+        fold_proof(digests, batch_opening_proof, point, data_transcript)?;
+
+    verify(&folded_digest, &folded_proof, point, vk)
+}
+
+/// Verifies the openings of several polynomials, each at its own point
+///
+/// # Arguments
+///
+/// * `digests` - The commitments to the polynomials
+/// * `proofs` - The opening proof of each polynomial
+/// * `points` - The point of evaluation of each polynomial
+/// * `vk` - The verifying key
+pub fn batch_verify_multi_points(
+    digests: &[Digest],
+    proofs: &[OpeningProof],
+    points: &[Fr],
+    vk: &KZGVerifyingKey,
+) -> Result<(), Error> {
+    let nb_digests = digests.len();
+    let nb_proofs = proofs.len();
+    let nb_points = points.len();
+
+    if nb_digests != nb_proofs {
+        return Err(Error::InvalidNumberOfDigests);
+    }
+
+    if nb_digests != nb_points {
+        return Err(Error::InvalidNumberOfDigests);
+    }
+
+    if nb_digests == 0 {
+        return Err(Error::InvalidNumberOfDigests);
+    }
+
+    if nb_digests == 1 {
+        return verify(&digests[0], &proofs[0], &points[0], vk);
+    }
+
+    let mut random_numbers = batch_coefficients(nb_digests, || {
+        let mut data = Vec::with_capacity(nb_digests * (64 + 64 + 32 + 32));
+        for ((digest, proof), point) in digests.iter().zip(proofs.iter()).zip(points.iter()) {
+            data.extend_from_slice(&g1_to_bytes(digest)?);
+            data.extend_from_slice(&g1_to_bytes(&proof.h)?);
+            data.extend_from_slice(&proof.claimed_value.into_u256().to_bytes_be());
+            data.extend_from_slice(&point.into_u256().to_bytes_be());
+        }
+        Ok(data)
+    })?;
+
+    let quotients: Vec<AffineG1> = proofs.iter().map(|proof| proof.h).collect();
+    let mut folded_quotients = AffineG1::msm(&quotients, &random_numbers);
+
+    let evals: Vec<Fr> = proofs.iter().map(|proof| proof.claimed_value).collect();
+
+    let (mut folded_digests, folded_evals) = fold(digests, &evals, &random_numbers);
+    let folded_evals_commit = vk.g1 * folded_evals;
+    folded_digests = folded_digests - folded_evals_commit.into();
+
+    for (random_number, point) in random_numbers.iter_mut().zip(points.iter()) {
+        *random_number *= *point;
+    }
+    let folded_points_quotients = AffineG1::msm(&quotients, &random_numbers);
+
+    folded_digests = folded_digests + folded_points_quotients;
+    folded_quotients = -folded_quotients;
+
+    let pairing_result = pairing_batch(&[
+        (folded_digests.into(), vk.g2[0]),
+        (folded_quotients.into(), vk.g2[1]),
+    ]);
+
+    if !pairing_result.is_one() {
+        return Err(Error::PairingCheckFailed);
+    }
+
+    Ok(())
+}
@@ -1,132 +1,464 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(missing_debug_implementations)]
 
 //! This crate provides verifiers for Groth16 and Plonk zero-knowledge proofs.
-use sp1_sdk::{SP1ProofWithPublicValues};
+//!
+//! Parsing and verification only need `alloc`, and report failures only through their error types.
+//! The wasm entry points, which read SP1 proofs, are behind the default `wasm` feature.
+extern crate alloc;
 
-use bn::Fr;
-use groth16::{
-    error::Groth16Error, load_groth16_proof_from_bytes, load_groth16_verifying_key_from_bytes,
-    verify_groth16,
-};
-use plonk::{
-    error::PlonkError, load_plonk_proof_from_bytes, load_plonk_verifying_key_from_bytes,
-    verify_plonk,
-};
-use wasm_bindgen::prelude::*;
-use serde_json::Value;
-use std::io::Read;
-use num_bigint::BigUint;
-use num_traits::Num;
-use serde_json;
-use hex;
-
-// see vk/circuits/src/main.rs
-const PLONK_VK_BYTES: &[u8] = include_bytes!("../../vk/plonk_vk.bin");
-const GROTH16_VK_BYTES: &[u8] = include_bytes!("../../vk/groth16_vk.bin");
+use alloc::{format, vec, vec::Vec};
+use groth16::{verify_groth16, verify_groth16_batch, verify_groth16_prepared};
+use plonk::{verify_plonk, verify_plonk_batch};
 
 mod constants;
 mod converter;
 mod error;
 mod groth16;
 mod hash_to_field;
+pub mod kzg;
+mod outcome;
 mod plonk;
+mod reader;
+mod sp1;
 mod transcript;
+#[cfg(feature = "wasm")]
+mod wasm;
 
-#[wasm_bindgen]
+pub use bn::Fr;
+pub use converter::{solidity_public_inputs, KeyValidation, PointEncoding};
+pub use error::Error;
+pub use groth16::{
+    error::Groth16Error, prepare_verifying_key, snarkjs_public_inputs, Groth16Proof,
+    Groth16VerifyingKey, PreparedVerifyingKey,
+};
+pub use outcome::VerificationOutcome;
+pub use plonk::{error::PlonkError, PlonkProof, PlonkVerifyingKey};
+pub use sp1::{
+    committed_values_digest, error::SP1Error, program_vkey_hash_to_fr, sp1_public_inputs,
+    SP1Calldata, SP1CircuitKey, SP1ProofBundle, SP1ProofJson, SP1ProofKind, VERIFY_PROOF_SELECTOR,
+};
+#[cfg(feature = "wasm")]
+pub use wasm::{
+    groth16_proof_to_solidity, plonk_proof_to_solidity, public_inputs_to_solidity,
+    sp1_gateway_calldata, verify_groth16_proof, verify_groth16_proof_batch,
+    verify_groth16_proof_with_inputs, verify_groth16_proofs, verify_json, verify_plonk_proof,
+    verify_plonk_proof_with_inputs, verify_proof, verify_snarkjs_groth16, verify_sp1,
+    verify_sp1_calldata, verify_sp1_onchain, ProofMode, VerificationResult,
+};
+
+/// Verifier for gnark Groth16 proofs over BN254.
 #[derive(Debug, Clone, Copy)]
-pub enum ProofMode {
-    Groth16,
-    Plonk,
-}
+pub struct Groth16Verifier;
 
+impl Groth16Verifier {
+    /// Verifies a gnark-serialized Groth16 proof against a gnark-serialized verifying key.
+    ///
+    /// Proof or verifying key bytes that cannot be decoded are reported as
+    /// [`VerificationOutcome::Malformed`].
+    pub fn verify(proof: &[u8], vk: &[u8], public_inputs: &[Fr]) -> VerificationOutcome {
+        Self::verify_with_validation(proof, vk, public_inputs, KeyValidation::Strict)
+    }
 
-#[wasm_bindgen]
-/// Test
-extern {
-    #[wasm_bindgen(js_namespace = console)]
-    pub fn log(s: &str);
-}
+    /// Same as [`Groth16Verifier::verify`], with the given validation of the verifying key points.
+    pub fn verify_with_validation(
+        proof: &[u8],
+        vk: &[u8],
+        public_inputs: &[Fr],
+        validation: KeyValidation,
+    ) -> VerificationOutcome {
+        let proof = match Groth16Proof::from_bytes(proof) {
+            Ok(proof) => proof,
+            Err(e) => return VerificationOutcome::malformed(format!("Invalid proof: {}", e)),
+        };
 
-// #[wasm_bindgen]
-/// WASM to verify a groth16 proof
-pub fn verify_groth16_wasm(proof: &[u8], vk: &[u8], public_inputs: &[Fr]) -> Result<bool, JsValue> {
-    let proof = load_groth16_proof_from_bytes(proof)
-        .map_err(|e| JsValue::from_str(&format!("Invalid proof: {}", e)))?;
-        
-    let vk = load_groth16_verifying_key_from_bytes(vk)
-        .map_err(|e| JsValue::from_str(&format!("Invalid verification key: {}", e)))?;
+        let vk = match Groth16VerifyingKey::from_bytes_with_validation(vk, validation) {
+            Ok(vk) => vk,
+            Err(e) => {
+                return VerificationOutcome::malformed(format!("Invalid verification key: {}", e))
+            }
+        };
+
+        Self::verify_parsed(&proof, &vk, public_inputs)
+    }
+
+    /// Verifies an already decoded Groth16 proof against an already decoded verifying key.
+    pub fn verify_parsed(
+        proof: &Groth16Proof,
+        vk: &Groth16VerifyingKey,
+        public_inputs: &[Fr],
+    ) -> VerificationOutcome {
+        verify_groth16(vk, proof, public_inputs)
+    }
+
+    /// Verifies a circom Groth16 proof from the `proof.json`, `verification_key.json` and
+    /// `public.json` files written by snarkjs.
+    ///
+    /// Files that cannot be decoded are reported as [`VerificationOutcome::Malformed`].
+    pub fn verify_snarkjs(proof: &str, vk: &str, public_inputs: &str) -> VerificationOutcome {
+        let proof = match Groth16Proof::from_snarkjs_json(proof) {
+            Ok(proof) => proof,
+            Err(e) => return VerificationOutcome::malformed(format!("Invalid proof: {}", e)),
+        };
+
+        let vk = match Groth16VerifyingKey::from_snarkjs_json(vk) {
+            Ok(vk) => vk,
+            Err(e) => {
+                return VerificationOutcome::malformed(format!("Invalid verification key: {}", e))
+            }
+        };
+
+        let public_inputs = match snarkjs_public_inputs(public_inputs) {
+            Ok(public_inputs) => public_inputs,
+            Err(e) => {
+                return VerificationOutcome::malformed(format!("Invalid public inputs: {}", e))
+            }
+        };
+
+        Self::verify_parsed(&proof, &vk, &public_inputs)
+    }
 
-    Ok(verify_groth16(&vk, &proof, public_inputs).is_ok())
+    /// Verifies a gnark-serialized Groth16 proof against a prepared verifying key.
+    ///
+    /// Prefer this over [`Groth16Verifier::verify`] when checking many proofs for the same circuit.
+    pub fn verify_prepared(
+        proof: &[u8],
+        pvk: &PreparedVerifyingKey,
+        public_inputs: &[Fr],
+    ) -> VerificationOutcome {
+        match Groth16Proof::from_bytes(proof) {
+            Ok(proof) => verify_groth16_prepared(pvk, &proof, public_inputs),
+            Err(e) => VerificationOutcome::malformed(format!("Invalid proof: {}", e)),
+        }
+    }
+
+    /// Verifies several gnark-serialized Groth16 proofs against the same prepared verifying key
+    /// with a single batched pairing check.
+    ///
+    /// Returns one outcome per proof, in order. Proofs that cannot be decoded are reported as
+    /// [`VerificationOutcome::Malformed`] and do not affect the others.
+    pub fn verify_batch(
+        proofs: &[(&[u8], &[Fr])],
+        pvk: &PreparedVerifyingKey,
+    ) -> Vec<VerificationOutcome> {
+        let mut outcomes = vec![VerificationOutcome::Valid; proofs.len()];
+        let mut decoded = Vec::with_capacity(proofs.len());
+        for (i, (proof, public_inputs)) in proofs.iter().enumerate() {
+            match Groth16Proof::from_bytes(proof) {
+                Ok(proof) => decoded.push((i, proof, *public_inputs)),
+                Err(e) => {
+                    outcomes[i] = VerificationOutcome::malformed(format!("Invalid proof: {}", e))
+                }
+            }
+        }
+
+        let batch: Vec<_> = decoded
+            .iter()
+            .map(|(_, proof, public_inputs)| (proof, *public_inputs))
+            .collect();
+        for ((i, _, _), outcome) in decoded.iter().zip(verify_groth16_batch(pvk, &batch)) {
+            outcomes[*i] = outcome;
+        }
+
+        outcomes
+    }
 }
 
+/// Verifier for gnark PlonK proofs over BN254.
+#[derive(Debug, Clone, Copy)]
+pub struct PlonkVerifier;
 
-// #[wasm_bindgen]
-/// WASM to verify a plonk proof
-pub fn verify_plonk_wasm(proof: &[u8], vk: &[u8], public_inputs: &[Fr]) -> Result<bool, JsValue> {
-    let proof = load_plonk_proof_from_bytes(proof)
-        .map_err(|e| JsValue::from_str(&format!("Invalid proof: {}", e)))?;
+impl PlonkVerifier {
+    /// Verifies a gnark-serialized PlonK proof against a gnark-serialized verifying key.
+    ///
+    /// Proof or verifying key bytes that cannot be decoded are reported as
+    /// [`VerificationOutcome::Malformed`].
+    pub fn verify(proof: &[u8], vk: &[u8], public_inputs: &[Fr]) -> VerificationOutcome {
+        Self::verify_with_validation(proof, vk, public_inputs, KeyValidation::Strict)
+    }
+
+    /// Same as [`PlonkVerifier::verify`], with the given validation of the verifying key points.
+    pub fn verify_with_validation(
+        proof: &[u8],
+        vk: &[u8],
+        public_inputs: &[Fr],
+        validation: KeyValidation,
+    ) -> VerificationOutcome {
+        let proof = match PlonkProof::from_bytes(proof) {
+            Ok(proof) => proof,
+            Err(e) => return VerificationOutcome::malformed(format!("Invalid proof: {}", e)),
+        };
+
+        let vk = match PlonkVerifyingKey::from_bytes_with_validation(vk, validation) {
+            Ok(vk) => vk,
+            Err(e) => {
+                return VerificationOutcome::malformed(format!("Invalid verification key: {}", e))
+            }
+        };
+
+        Self::verify_parsed(&proof, &vk, public_inputs)
+    }
+
+    /// Verifies an already decoded PlonK proof against an already decoded verifying key.
+    pub fn verify_parsed(
+        proof: &PlonkProof,
+        vk: &PlonkVerifyingKey,
+        public_inputs: &[Fr],
+    ) -> VerificationOutcome {
+        verify_plonk(vk, proof, public_inputs)
+    }
 
-    let vk = load_plonk_verifying_key_from_bytes(vk)
-        .map_err(|e| JsValue::from_str(&format!("Invalid verification key: {}", e)))?;
+    /// Verifies several gnark-serialized PlonK proofs against the same verifying key with a single
+    /// batched pairing check.
+    ///
+    /// Returns one outcome per proof, in order. Proofs that cannot be decoded are reported as
+    /// [`VerificationOutcome::Malformed`] and do not affect the others.
+    pub fn verify_batch(
+        proofs: &[(&[u8], &[Fr])],
+        vk: &PlonkVerifyingKey,
+    ) -> Vec<VerificationOutcome> {
+        let mut outcomes = vec![VerificationOutcome::Valid; proofs.len()];
+        let mut decoded = Vec::with_capacity(proofs.len());
+        for (i, (proof, public_inputs)) in proofs.iter().enumerate() {
+            match PlonkProof::from_bytes(proof) {
+                Ok(proof) => decoded.push((i, proof, *public_inputs)),
+                Err(e) => {
+                    outcomes[i] = VerificationOutcome::malformed(format!("Invalid proof: {}", e))
+                }
+            }
+        }
 
-    Ok(verify_plonk(&vk, &proof, public_inputs).is_ok())
+        let batch: Vec<_> = decoded
+            .iter()
+            .map(|(_, proof, public_inputs)| (proof, *public_inputs))
+            .collect();
+        for ((i, _, _), outcome) in decoded.iter().zip(verify_plonk_batch(vk, &batch)) {
+            outcomes[*i] = outcome;
+        }
+
+        outcomes
+    }
 }
 
-#[wasm_bindgen]
-/// WASM to verify a proof using SP1 to read proof and public inputs
-pub fn verify_proof(contents: &[u8], method: ProofMode) -> Result<bool, JsValue> {
-
-    // Load the saved proof and convert it to the specified proof mode
-    // Passing the bytes directly instead of SP1ProofWithPublicValues::load()
-    let sp1_proof_with_public_values: SP1ProofWithPublicValues = bincode::deserialize(contents)
-        .expect("Failed to deserialize proof.");
-    let (raw_proof, public_inputs) = match method {
-        ProofMode::Groth16 => {
-            // log(&format!("Proof mode: Groth16"));
-            let proof = sp1_proof_with_public_values
-                .proof
-                .try_as_groth_16()
-                .map_or(Err(JsValue::from_str("Failed to get Groth16 proof")), |p| Ok(p))?;
-            let raw_proof = hex::decode(&proof.raw_proof)
-                .map_or(Err(JsValue::from_str("Failed to decode Groth16 proof hex")), |r| Ok(r))?;
-            (raw_proof, proof.public_inputs)
+/// Verifier for SP1 proofs wrapped in a PlonK or Groth16 proof, against the SP1 circuit verifying
+/// keys embedded in this crate.
+#[derive(Debug, Clone, Copy)]
+pub struct SP1Verifier;
+
+impl SP1Verifier {
+    /// Verifies that a gnark-serialized proof attests to an execution of the program with the given
+    /// verifying key hash that committed the given public values, against the circuit verifying
+    /// key of the most recent embedded SP1 version.
+    ///
+    /// `program_vkey_hash` is the `0x`-prefixed bytes32 hash given by `SP1VerifyingKey::bytes32`.
+    /// The public inputs are rebuilt from it and from `public_values`, so proofs of any other
+    /// program or public values are reported as [`VerificationOutcome::Invalid`].
+    ///
+    /// Proofs of other SP1 versions are verified by [`SP1Verifier::verify_onchain`], which picks
+    /// the key from the proof.
+    pub fn verify(
+        proof: &[u8],
+        public_values: &[u8],
+        program_vkey_hash: &str,
+        kind: SP1ProofKind,
+    ) -> VerificationOutcome {
+        Self::verify_with_key(
+            proof,
+            public_values,
+            program_vkey_hash,
+            SP1CircuitKey::latest(kind),
+        )
+    }
+
+    /// Same as [`SP1Verifier::verify`], for the proof as `SP1ProofWithPublicValues::bytes` returns
+    /// it: the 4-byte verifier selector, the first bytes of the hash of the circuit verifying key,
+    /// followed by the proof in the encoding of the gnark Solidity verifiers. The proof is checked
+    /// against the embedded circuit verifying key its selector picks, and proofs whose selector
+    /// matches no embedded key of the given kind are reported as
+    /// [`VerificationOutcome::Malformed`].
+    pub fn verify_onchain(
+        proof: &[u8],
+        public_values: &[u8],
+        program_vkey_hash: &str,
+        kind: SP1ProofKind,
+    ) -> VerificationOutcome {
+        Self::verify_onchain_with_keys(
+            proof,
+            public_values,
+            program_vkey_hash,
+            kind,
+            SP1CircuitKey::all(),
+        )
+    }
+
+    /// Same as [`SP1Verifier::verify_onchain`], picking the circuit verifying key among `keys`,
+    /// e.g. to verify proofs of an SP1 release whose keys are not embedded in this crate.
+    pub fn verify_onchain_with_keys(
+        proof: &[u8],
+        public_values: &[u8],
+        program_vkey_hash: &str,
+        kind: SP1ProofKind,
+        keys: &[SP1CircuitKey],
+    ) -> VerificationOutcome {
+        if proof.len() < 4 {
+            return VerificationOutcome::malformed("Invalid proof: missing verifier selector");
+        }
+        let (selector, proof) = proof.split_at(4);
+        let key = match keys
+            .iter()
+            .find(|key| key.kind == kind && key.hash().starts_with(selector))
+        {
+            Some(key) => key,
+            None => {
+                return VerificationOutcome::malformed(SP1Error::UnknownVerifierSelector(
+                    hex::encode(selector),
+                ))
+            }
+        };
+
+        let public_inputs = match sp1_public_inputs(program_vkey_hash, public_values) {
+            Ok(public_inputs) => public_inputs,
+            Err(e) => return VerificationOutcome::malformed(e),
+        };
+
+        Self::verify_onchain_proof(proof, key, &public_inputs)
+    }
+
+    /// Same as [`SP1Verifier::verify`], against the given circuit verifying key.
+    pub fn verify_with_key(
+        proof: &[u8],
+        public_values: &[u8],
+        program_vkey_hash: &str,
+        key: &SP1CircuitKey,
+    ) -> VerificationOutcome {
+        let public_inputs = match sp1_public_inputs(program_vkey_hash, public_values) {
+            Ok(public_inputs) => public_inputs,
+            Err(e) => return VerificationOutcome::malformed(e),
+        };
+
+        Self::verify_with_embedded_key(proof, key, &public_inputs)
+    }
+
+    // The embedded circuit keys are trusted, so their points are not validated on every proof
+    fn verify_with_embedded_key(
+        proof: &[u8],
+        key: &SP1CircuitKey,
+        public_inputs: &[Fr],
+    ) -> VerificationOutcome {
+        match key.kind {
+            SP1ProofKind::Groth16 => Groth16Verifier::verify_with_validation(
+                proof,
+                key.bytes,
+                public_inputs,
+                KeyValidation::Trusted,
+            ),
+            SP1ProofKind::Plonk => PlonkVerifier::verify_with_validation(
+                proof,
+                key.bytes,
+                public_inputs,
+                KeyValidation::Trusted,
+            ),
         }
-        ProofMode::Plonk => {
-            // log(&format!("Proof mode: Plonk"));
-            let proof = sp1_proof_with_public_values
-                .proof
-                .try_as_plonk()
-                .map_or(Err(JsValue::from_str("Failed to get Plonk proof")), |p| Ok(p))?;
-            let raw_proof = hex::decode(&proof.raw_proof)
-                .map_or(Err(JsValue::from_str("Failed to decode Plonk proof hex")), |r| Ok(r))?;
-            (raw_proof, proof.public_inputs)
+    }
+
+    /// Verifies a proof in the JSON format of `examples/binaries/*.bin.json`, against the embedded
+    /// circuit verifying key given by [`SP1ProofJson::circuit_key`]: the one whose hash is its
+    /// `circuit_vkey_hash`, or the one of the most recent SP1 version if it has none.
+    ///
+    /// The JSON carries the public inputs but not the public values, so this only shows that the
+    /// program with the verifying key hash in the JSON committed to its `committed_values_digest`.
+    pub fn verify_json(json: &str, kind: SP1ProofKind) -> VerificationOutcome {
+        let proof = match SP1ProofJson::from_json(json) {
+            Ok(proof) => proof,
+            Err(e) => return VerificationOutcome::malformed(e),
+        };
+
+        match proof.circuit_key(kind) {
+            Ok(key) => Self::verify_with_embedded_key(&proof.raw_proof, key, &proof.public_inputs),
+            Err(e) => VerificationOutcome::malformed(e),
         }
-        _ => return Err(JsValue::from_str("Invalid proof mode. Use 'groth16' or 'plonk'.")),
-    };
-
-    // Convert public inputs to byte representations
-    let vkey_hash = BigUint::from_str_radix(&public_inputs[0], 10)
-        .unwrap()
-        .to_bytes_be();
-    let committed_values_digest = BigUint::from_str_radix(&public_inputs[1], 10)
-        .unwrap()
-        .to_bytes_be();
-
-    let vkey_hash = Fr::from_slice(&vkey_hash).expect("Unable to read vkey_hash");
-    let committed_values_digest = Fr::from_slice(&committed_values_digest)
-        .expect("Unable to read committed_values_digest");
-
-    // Read VK from the appropriate binary
-    let vk_bytes = match method {
-        ProofMode::Groth16 => GROTH16_VK_BYTES,
-        ProofMode::Plonk => PLONK_VK_BYTES,
-    };
-
-    // Call the appropriate verification function based on the method
-    match method {
-        ProofMode::Groth16 => verify_groth16_wasm(&raw_proof, &vk_bytes,  &[vkey_hash, committed_values_digest]),
-        ProofMode::Plonk => verify_plonk_wasm(&raw_proof, &vk_bytes, &[vkey_hash, committed_values_digest]),
-        _ => Err(JsValue::from_str("Invalid proof mode")),
-    }
-}
\ No newline at end of file
+    }
+
+    /// Verifies an SP1 proof bundle for the program with the given verifying key hash, against the
+    /// circuit verifying key of the SP1 version it was generated with.
+    pub fn verify_bundle(bundle: &SP1ProofBundle, program_vkey_hash: &str) -> VerificationOutcome {
+        match bundle.circuit_key() {
+            Ok(key) => Self::verify_with_key(
+                &bundle.raw_proof,
+                &bundle.public_values,
+                program_vkey_hash,
+                key,
+            ),
+            Err(e) => VerificationOutcome::malformed(e),
+        }
+    }
+
+    /// Verifies the proof submitted in a `verifyProof` call to the SP1 verifier gateway, against
+    /// the embedded circuit verifying key its verifier selector routes to.
+    ///
+    /// The public inputs are rebuilt from the program verifying key hash and public values in the
+    /// calldata, as the onchain verifier does.
+    pub fn verify_calldata(calldata: &SP1Calldata) -> VerificationOutcome {
+        let key = match calldata.circuit_key() {
+            Ok(key) => key,
+            Err(e) => return VerificationOutcome::malformed(e),
+        };
+
+        let public_inputs = match calldata.public_inputs() {
+            Ok(public_inputs) => public_inputs,
+            Err(e) => return VerificationOutcome::malformed(e),
+        };
+
+        Self::verify_onchain_proof(&calldata.proof, key, &public_inputs)
+    }
+
+    // Verifies a proof in the encoding of the gnark Solidity verifiers, without its verifier
+    // selector, against a trusted circuit key
+    fn verify_onchain_proof(
+        proof: &[u8],
+        key: &SP1CircuitKey,
+        public_inputs: &[Fr],
+    ) -> VerificationOutcome {
+        match key.kind {
+            SP1ProofKind::Groth16 => {
+                let vk = match Groth16VerifyingKey::from_bytes_with_validation(
+                    key.bytes,
+                    KeyValidation::Trusted,
+                ) {
+                    Ok(vk) => vk,
+                    Err(e) => {
+                        return VerificationOutcome::malformed(format!(
+                            "Invalid verification key: {}",
+                            e
+                        ))
+                    }
+                };
+
+                match Groth16Proof::from_solidity_bytes(proof) {
+                    Ok(proof) => Groth16Verifier::verify_parsed(&proof, &vk, public_inputs),
+                    Err(e) => VerificationOutcome::malformed(format!("Invalid proof: {}", e)),
+                }
+            }
+            SP1ProofKind::Plonk => {
+                let vk = match PlonkVerifyingKey::from_bytes_with_validation(
+                    key.bytes,
+                    KeyValidation::Trusted,
+                ) {
+                    Ok(vk) => vk,
+                    Err(e) => {
+                        return VerificationOutcome::malformed(format!(
+                            "Invalid verification key: {}",
+                            e
+                        ))
+                    }
+                };
+
+                match PlonkProof::from_solidity_bytes(proof, &vk, public_inputs) {
+                    Ok(proof) => PlonkVerifier::verify_parsed(&proof, &vk, public_inputs),
+                    Err(e) => VerificationOutcome::malformed(format!("Invalid proof: {}", e)),
+                }
+            }
+        }
+    }
+}
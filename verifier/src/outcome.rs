@@ -0,0 +1,35 @@
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+
+/// The result of verifying a proof.
+///
+/// Distinguishes a proof that does not verify from inputs that could not be checked at all, so a
+/// failure is never mistaken for success.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationOutcome {
+    /// The proof verifies against the verifying key and public inputs.
+    Valid,
+    /// The inputs are well-formed but the proof does not verify.
+    Invalid { reason: String },
+    /// The proof, verifying key or public inputs are malformed or inconsistent with each other.
+    Malformed { error: String },
+}
+
+impl VerificationOutcome {
+    /// Returns true only for [`VerificationOutcome::Valid`].
+    pub fn is_valid(&self) -> bool {
+        matches!(self, VerificationOutcome::Valid)
+    }
+
+    pub(crate) fn invalid(reason: impl Display) -> Self {
+        VerificationOutcome::Invalid {
+            reason: reason.to_string(),
+        }
+    }
+
+    pub(crate) fn malformed(error: impl Display) -> Self {
+        VerificationOutcome::Malformed {
+            error: error.to_string(),
+        }
+    }
+}
@@ -1,135 +1,81 @@
+use alloc::{vec, vec::Vec};
+
 use crate::{
-    converter::{
-        unchecked_compressed_x_to_g1_point, unchecked_compressed_x_to_g2_point,
-        uncompressed_bytes_to_g1_point,
-    },
+    converter::{g1_to_bytes, KeyValidation, PointEncoding},
     error::Error,
+    kzg::{BatchOpeningProof, KZGVerifyingKey, OpeningProof},
+    reader::Reader,
 };
-use bn::{AffineG1, Fr, G2};
-
-use super::{
-    error::PlonkError,
-    kzg::{self, BatchOpeningProof, LineEvaluationAff, OpeningProof, E2},
-    verify::PlonkVerifyingKey,
-    PlonkProof,
-};
+use bn::Fr;
 
-use crate::wasm_bindgen;
-
-#[wasm_bindgen]
-/// Test
-extern {
-    #[wasm_bindgen(js_namespace = console)]
-    pub fn log(s: &str);
-}
+use super::{error::PlonkError, verify::PlonkVerifyingKey, PlonkProof};
 
+// Reads a verifying key written by gnark's `WriteTo` or `WriteRawTo`, with compressed or
+// uncompressed points
 pub(crate) fn load_plonk_verifying_key_from_bytes(
     buffer: &[u8],
+    validation: KeyValidation,
 ) -> Result<PlonkVerifyingKey, PlonkError> {
+    // The points start after the domain size, its inverse and generator, the number of public
+    // variables and the coset shift
+    let encoding = PointEncoding::detect(buffer.get(112..).unwrap_or_default());
+    let mut reader = Reader::new(buffer, encoding);
 
-    // Minimum size of the buffer is 372 bytes
-    if buffer.len() < 372 {
-        log(&format!("Insufficient buffer length: expected at least 372 bytes, got {}", buffer.len()));
-        return Err(PlonkError::GeneralError(Error::InvalidXLength));
-    }
-
-    let size = u64::from_be_bytes([
-        buffer[0], buffer[1], buffer[2], buffer[3], buffer[4], buffer[5], buffer[6], buffer[7],
-    ]) as usize;
-    let size_inv =
-        Fr::from_slice(&buffer[8..40]).map_err(|e| PlonkError::GeneralError(Error::Field(e)))?;
-    let generator =
-        Fr::from_slice(&buffer[40..72]).map_err(|e| PlonkError::GeneralError(Error::Field(e)))?;
-
-    let nb_public_variables = u64::from_be_bytes([
-        buffer[72], buffer[73], buffer[74], buffer[75], buffer[76], buffer[77], buffer[78],
-        buffer[79],
-    ]) as usize;
-
-    let coset_shift =
-        Fr::from_slice(&buffer[80..112]).map_err(|e| PlonkError::GeneralError(Error::Field(e)))?;
-    let s0 = unchecked_compressed_x_to_g1_point(&buffer[112..144])?;
-    let s1 = unchecked_compressed_x_to_g1_point(&buffer[144..176])?;
-    let s2 = unchecked_compressed_x_to_g1_point(&buffer[176..208])?;
-    let ql = unchecked_compressed_x_to_g1_point(&buffer[208..240])?;
-    let qr = unchecked_compressed_x_to_g1_point(&buffer[240..272])?;
-    let qm = unchecked_compressed_x_to_g1_point(&buffer[272..304])?;
-    let qo = unchecked_compressed_x_to_g1_point(&buffer[304..336])?;
-    let qk = unchecked_compressed_x_to_g1_point(&buffer[336..368])?;
-    let num_qcp = u32::from_be_bytes([buffer[368], buffer[369], buffer[370], buffer[371]]);
-    let mut qcp = Vec::new();
-    let mut offset = 372;
-
-    // Check if buffer has enough bytes for all qcp points
-    if buffer.len() < (offset + 32 * num_qcp as usize) {
-        log(&format!("Insufficient buffer length: expected at least {} bytes, got {}", offset + 32 * num_qcp as usize, buffer.len()));
-        return Err(PlonkError::GeneralError(Error::InvalidXLength));
+    // gnark's evaluation domains have a power of two size
+    let size = reader.read_u64_as_usize("domain size")?;
+    if !size.is_power_of_two() {
+        return Err(PlonkError::GeneralError(Error::InvalidField {
+            field: "domain size",
+            offset: 0,
+        }));
     }
+    let size_inv = reader.read_fr("inverse of the domain size")?;
+    let generator = reader.read_fr("domain generator")?;
+    let nb_public_variables = reader.read_u64_as_usize("number of public variables")?;
+    let coset_shift = reader.read_fr("coset shift")?;
 
-    for _ in 0..num_qcp {
-        let point = unchecked_compressed_x_to_g1_point(&buffer[offset..offset + 32])?;
-        qcp.push(point);
-        offset += 32;
-    }
+    let s0 = reader.read_key_g1("S1", validation)?;
+    let s1 = reader.read_key_g1("S2", validation)?;
+    let s2 = reader.read_key_g1("S3", validation)?;
+    let ql = reader.read_key_g1_or_zero("Ql", validation)?;
+    let qr = reader.read_key_g1_or_zero("Qr", validation)?;
+    let qm = reader.read_key_g1_or_zero("Qm", validation)?;
+    let qo = reader.read_key_g1_or_zero("Qo", validation)?;
+    let qk = reader.read_key_g1_or_zero("Qk", validation)?;
 
-    if buffer.len() < (offset + 160 + 33788) {
-        log(&format!("Insufficient buffer length: expected at least {} bytes, got {}", offset + 160 + 33788, buffer.len()));
-        return Err(PlonkError::GeneralError(Error::InvalidXLength));
+    let num_qcp = reader.read_len("number of Qcp")?;
+    let mut qcp = Vec::new();
+    for _ in 0..num_qcp {
+        qcp.push(reader.read_key_g1_or_zero("Qcp", validation)?);
     }
 
-    let g1 = unchecked_compressed_x_to_g1_point(&buffer[offset..offset + 32])?;
-    let g2_0 = unchecked_compressed_x_to_g2_point(&buffer[offset + 32..offset + 96])?;
-    let g2_1 = unchecked_compressed_x_to_g2_point(&buffer[offset + 96..offset + 160])?;
+    // G₁, G₂, [α]G₂ followed by the precomputed lines, then the commitment constraint indexes
+    let kzg = KZGVerifyingKey::read(&mut reader, validation)?;
 
-    offset += 160 + 33788;
-
-    let num_commitment_constraint_indexes = u64::from_be_bytes([
-        buffer[offset],
-        buffer[offset + 1],
-        buffer[offset + 2],
-        buffer[offset + 3],
-        buffer[offset + 4],
-        buffer[offset + 5],
-        buffer[offset + 6],
-        buffer[offset + 7],
-    ]) as usize;
+    // gnark commits to one constraint per Qcp
+    let field = "number of commitment constraint indexes";
+    let offset = reader.offset();
+    let num_commitment_constraint_indexes = reader.read_len(field)?;
+    if num_commitment_constraint_indexes != qcp.len() {
+        return Err(PlonkError::GeneralError(Error::InvalidField {
+            field,
+            offset,
+        }));
+    }
 
     let mut commitment_constraint_indexes = Vec::new();
-    offset += 8;
     for _ in 0..num_commitment_constraint_indexes {
-        let index = u64::from_be_bytes([
-            buffer[offset],
-            buffer[offset + 1],
-            buffer[offset + 2],
-            buffer[offset + 3],
-            buffer[offset + 4],
-            buffer[offset + 5],
-            buffer[offset + 6],
-            buffer[offset + 7],
-        ]) as usize;
-        commitment_constraint_indexes.push(index);
-        offset += 8;
+        commitment_constraint_indexes
+            .push(reader.read_u64_as_usize("commitment constraint index")?);
     }
+    reader.finish("PlonK verifying key")?;
 
     let result = PlonkVerifyingKey {
         size,
         size_inv,
         generator,
         nb_public_variables,
-        kzg: kzg::KZGVerifyingKey {
-            g2: [G2::from(g2_0), G2::from(g2_1)],
-            g1: g1.into(),
-            lines: [[[LineEvaluationAff {
-                r0: E2 {
-                    a0: Fr::zero(),
-                    a1: Fr::zero(),
-                },
-                r1: E2 {
-                    a0: Fr::zero(),
-                    a1: Fr::zero(),
-                },
-            }; 66]; 2]; 2],
-        },
+        kzg,
         coset_shift,
         s: [s0, s1, s2],
         ql,
@@ -144,63 +90,34 @@ pub(crate) fn load_plonk_verifying_key_from_bytes(
     Ok(result)
 }
 
+// Reads a proof written by gnark's `WriteRawTo` or `WriteTo`, with uncompressed or compressed points
 pub(crate) fn load_plonk_proof_from_bytes(buffer: &[u8]) -> Result<PlonkProof, PlonkError> {
-    // Minimum size of the buffer is 516 bytes
-    if buffer.len() < 516 {
-        log(&format!("Insufficient buffer length: expected at least 516 bytes, got {}", buffer.len()));
-        return Err(PlonkError::GeneralError(Error::InvalidXLength));
-    }
-
-    let lro0 = uncompressed_bytes_to_g1_point(&buffer[..64])?;
-    let lro1 = uncompressed_bytes_to_g1_point(&buffer[64..128])?;
-    let lro2 = uncompressed_bytes_to_g1_point(&buffer[128..192])?;
-    let z = uncompressed_bytes_to_g1_point(&buffer[192..256])?;
-    let h0 = uncompressed_bytes_to_g1_point(&buffer[256..320])?;
-    let h1 = uncompressed_bytes_to_g1_point(&buffer[320..384])?;
-    let h2 = uncompressed_bytes_to_g1_point(&buffer[384..448])?;
-    let batched_proof_h = uncompressed_bytes_to_g1_point(&buffer[448..512])?;
-
-    let num_claimed_values =
-        u32::from_be_bytes([buffer[512], buffer[513], buffer[514], buffer[515]]) as usize;
+    let mut reader = Reader::new(buffer, PointEncoding::detect(buffer));
 
-    if buffer.len() < (516 + 32 * num_claimed_values) {
-        log(&format!("Insufficient buffer length: expected at least {} bytes, got {}", 516 + 32 * num_claimed_values, buffer.len()));
-        return Err(PlonkError::GeneralError(Error::InvalidXLength));
-    }
+    let lro0 = reader.read_g1("L")?;
+    let lro1 = reader.read_g1("R")?;
+    let lro2 = reader.read_g1("O")?;
+    let z = reader.read_g1("Z")?;
+    let h0 = reader.read_g1("H1")?;
+    let h1 = reader.read_g1("H2")?;
+    let h2 = reader.read_g1("H3")?;
+    let batched_proof_h = reader.read_g1("batched opening proof")?;
 
+    let num_claimed_values = reader.read_len("number of claimed values")?;
     let mut claimed_values = Vec::new();
-    let mut offset = 516;
     for _ in 0..num_claimed_values {
-        let value = Fr::from_slice(&buffer[offset..offset + 32])
-            .map_err(|e| PlonkError::GeneralError(Error::Field(e)))?;
-        claimed_values.push(value);
-        offset += 32;
+        claimed_values.push(reader.read_fr("claimed value")?);
     }
 
-    let z_shifted_opening_h = uncompressed_bytes_to_g1_point(&buffer[offset..offset + 64])?;
-    let z_shifted_opening_value = Fr::from_slice(&buffer[offset + 64..offset + 96])
-        .map_err(|e| PlonkError::GeneralError(Error::Field(e)))?;
-
-    let num_bsb22_commitments = u32::from_be_bytes([
-        buffer[offset + 96],
-        buffer[offset + 97],
-        buffer[offset + 98],
-        buffer[offset + 99],
-    ]) as usize;
+    let z_shifted_opening_h = reader.read_g1("shifted opening proof of Z")?;
+    let z_shifted_opening_value = reader.read_fr("shifted claimed value of Z")?;
 
+    let num_bsb22_commitments = reader.read_len("number of BSB22 commitments")?;
     let mut bsb22_commitments = Vec::new();
-    offset += 100;
-
-    if buffer.len() < (offset + 64 * num_bsb22_commitments) {
-        log(&format!("Insufficient buffer length: expected at least {} bytes, got {}", offset + 64 * num_bsb22_commitments, buffer.len()));
-        return Err(PlonkError::GeneralError(Error::InvalidXLength));
-    }
-    
     for _ in 0..num_bsb22_commitments {
-        let commitment = uncompressed_bytes_to_g1_point(&buffer[offset..offset + 64])?;
-        bsb22_commitments.push(commitment);
-        offset += 64;
+        bsb22_commitments.push(reader.read_g1("BSB22 commitment")?);
     }
+    reader.finish("PlonK proof")?;
 
     let result = PlonkProof {
         lro: [lro0, lro1, lro2],
@@ -220,13 +137,154 @@ pub(crate) fn load_plonk_proof_from_bytes(buffer: &[u8]) -> Result<PlonkProof, P
     Ok(result)
 }
 
-pub(crate) fn g1_to_bytes(g1: &AffineG1) -> Result<Vec<u8>, PlonkError> {
-    // Minimum size of the buffer is 64 bytes
-    // TODO: Check if the point is in the correct subgroup
+// Loads a proof encoded for the gnark Solidity verifier: the commitments and openings as 32-byte words, without the
+// opening of the linearized polynomial, which is left as zero for the caller to fill in.
+pub(crate) fn load_plonk_proof_from_solidity_bytes(
+    buffer: &[u8],
+    nb_commitments: usize,
+) -> Result<PlonkProof, PlonkError> {
+    let mut reader = Reader::new(buffer, PointEncoding::Uncompressed);
+
+    let lro = [
+        reader.read_g1("L")?,
+        reader.read_g1("R")?,
+        reader.read_g1("O")?,
+    ];
+    let h = [
+        reader.read_g1("H1")?,
+        reader.read_g1("H2")?,
+        reader.read_g1("H3")?,
+    ];
+
+    // Linearized polynomial, l(ζ), r(ζ), o(ζ), s1(ζ), s2(ζ), then the Qcp openings
+    let mut claimed_values = vec![Fr::zero()];
+    for _ in 0..5 {
+        claimed_values.push(reader.read_fr("claimed value")?);
+    }
+
+    let z = reader.read_g1("Z")?;
+    let z_shifted_opening_value = reader.read_fr("shifted claimed value of Z")?;
+    let batched_proof_h = reader.read_g1("batched opening proof")?;
+    let z_shifted_opening_h = reader.read_g1("shifted opening proof of Z")?;
+
+    for _ in 0..nb_commitments {
+        claimed_values.push(reader.read_fr("claimed value")?);
+    }
+    let mut bsb22_commitments = Vec::new();
+    for _ in 0..nb_commitments {
+        bsb22_commitments.push(reader.read_g1("BSB22 commitment")?);
+    }
+    reader.finish("Solidity proof")?;
+
+    Ok(PlonkProof {
+        lro,
+        z,
+        h,
+        bsb22_commitments,
+        batched_proof: BatchOpeningProof {
+            h: batched_proof_h,
+            claimed_values,
+        },
+        z_shifted_opening: OpeningProof {
+            h: z_shifted_opening_h,
+            claimed_value: z_shifted_opening_value,
+        },
+    })
+}
+
+// Encode a proof for the gnark Solidity verifier, in the layout read by `load_plonk_proof_from_solidity_bytes`
+pub(crate) fn plonk_proof_to_solidity_bytes(proof: &PlonkProof) -> Result<Vec<u8>, PlonkError> {
+    let claimed_values = &proof.batched_proof.claimed_values;
+    if claimed_values.len() != 6 + proof.bsb22_commitments.len() {
+        return Err(PlonkError::GeneralError(Error::Bsb22CommitmentMismatch));
+    }
+
+    let mut bytes = Vec::with_capacity(0x300 + 96 * proof.bsb22_commitments.len());
+    for point in proof.lro.iter().chain(proof.h.iter()) {
+        bytes.extend_from_slice(&g1_to_bytes(point)?);
+    }
+    // The opening of the linearized polynomial is recomputed by the verifier
+    for value in &claimed_values[1..6] {
+        bytes.extend_from_slice(&value.into_u256().to_bytes_be());
+    }
+    bytes.extend_from_slice(&g1_to_bytes(&proof.z)?);
+    let z_shifted_value = proof.z_shifted_opening.claimed_value;
+    bytes.extend_from_slice(&z_shifted_value.into_u256().to_bytes_be());
+    bytes.extend_from_slice(&g1_to_bytes(&proof.batched_proof.h)?);
+    bytes.extend_from_slice(&g1_to_bytes(&proof.z_shifted_opening.h)?);
+    for value in &claimed_values[6..] {
+        bytes.extend_from_slice(&value.into_u256().to_bytes_be());
+    }
+    for commitment in &proof.bsb22_commitments {
+        bytes.extend_from_slice(&g1_to_bytes(commitment)?);
+    }
+
+    Ok(bytes)
+}
+
+// Inverse of `load_plonk_verifying_key_from_bytes`, with the KZG verifying key followed by all-zero
+// pairing lines
+pub(crate) fn plonk_verifying_key_to_bytes(
+    vk: &PlonkVerifyingKey,
+    encoding: PointEncoding,
+) -> Result<Vec<u8>, PlonkError> {
+    let mut bytes = Vec::with_capacity(116 + encoding.g1_len() * (8 + vk.qcp.len()));
+    bytes.extend_from_slice(&(vk.size as u64).to_be_bytes());
+    bytes.extend_from_slice(&vk.size_inv.into_u256().to_bytes_be());
+    bytes.extend_from_slice(&vk.generator.into_u256().to_bytes_be());
+    bytes.extend_from_slice(&(vk.nb_public_variables as u64).to_be_bytes());
+    bytes.extend_from_slice(&vk.coset_shift.into_u256().to_bytes_be());
+
+    for point in vk.s.iter().chain([&vk.ql, &vk.qr, &vk.qm, &vk.qo, &vk.qk]) {
+        encoding.write_g1(&mut bytes, point)?;
+    }
+    bytes.extend_from_slice(&(vk.qcp.len() as u32).to_be_bytes());
+    for point in &vk.qcp {
+        encoding.write_g1(&mut bytes, point)?;
+    }
 
-    let mut bytes = Vec::with_capacity(64);
-    bytes.extend_from_slice(&g1.x().0.0.to_bytes_be());
-    bytes.extend_from_slice(&g1.y().0.0.to_bytes_be());
+    vk.kzg.write(&mut bytes, encoding)?;
+
+    bytes.extend_from_slice(&(vk.commitment_constraint_indexes.len() as u32).to_be_bytes());
+    for index in &vk.commitment_constraint_indexes {
+        bytes.extend_from_slice(&(*index as u64).to_be_bytes());
+    }
+
+    Ok(bytes)
+}
+
+// Inverse of `load_plonk_proof_from_bytes`
+pub(crate) fn plonk_proof_to_bytes(
+    proof: &PlonkProof,
+    encoding: PointEncoding,
+) -> Result<Vec<u8>, PlonkError> {
+    let claimed_values = &proof.batched_proof.claimed_values;
+    let mut bytes = Vec::with_capacity(
+        encoding.g1_len() * (9 + proof.bsb22_commitments.len()) + 40 + 32 * claimed_values.len(),
+    );
+    for point in proof
+        .lro
+        .iter()
+        .chain([&proof.z])
+        .chain(proof.h.iter())
+        .chain([&proof.batched_proof.h])
+    {
+        encoding.write_g1(&mut bytes, point)?;
+    }
+
+    bytes.extend_from_slice(&(claimed_values.len() as u32).to_be_bytes());
+    for value in claimed_values {
+        bytes.extend_from_slice(&value.into_u256().to_bytes_be());
+    }
+
+    let z_shifted_opening = &proof.z_shifted_opening;
+    encoding.write_g1(&mut bytes, &z_shifted_opening.h)?;
+    bytes.extend_from_slice(&z_shifted_opening.claimed_value.into_u256().to_bytes_be());
+
+    bytes.extend_from_slice(&(proof.bsb22_commitments.len() as u32).to_be_bytes());
+    for commitment in &proof.bsb22_commitments {
+        encoding.write_g1(&mut bytes, commitment)?;
+    }
 
     Ok(bytes)
 }
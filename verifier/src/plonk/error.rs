@@ -42,6 +42,6 @@ pub enum PlonkError {
     TranscriptError,
     #[error("Hash to field initialization failed")]
     HashToFieldInitializationFailed,
-    #[error("General error")]
+    #[error("General error: {0}")]
     GeneralError(#[from] crate::error::Error),
 }
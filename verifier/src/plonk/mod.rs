@@ -1,10 +1,10 @@
 mod converter;
-mod kzg;
 mod proof;
 mod verify;
 
 pub(crate) mod error;
 
-pub(crate) use converter::{load_plonk_proof_from_bytes, load_plonk_verifying_key_from_bytes};
-pub(crate) use proof::PlonkProof;
-pub(crate) use verify::verify_plonk;
+pub use proof::PlonkProof;
+pub use verify::PlonkVerifyingKey;
+
+pub(crate) use verify::{verify_plonk, verify_plonk_batch};
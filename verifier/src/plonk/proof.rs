@@ -1,7 +1,24 @@
-use super::kzg::{BatchOpeningProof, Digest, OpeningProof};
+use alloc::vec::Vec;
+use bn::Fr;
 
+use crate::{
+    converter::PointEncoding,
+    kzg::{BatchOpeningProof, Digest, OpeningProof},
+};
+
+use super::{
+    converter::{
+        load_plonk_proof_from_bytes, load_plonk_proof_from_solidity_bytes, plonk_proof_to_bytes,
+        plonk_proof_to_solidity_bytes,
+    },
+    error::PlonkError,
+    verify::linearized_polynomial_opening,
+    PlonkVerifyingKey,
+};
+
+/// A PlonK proof, as serialized by gnark.
 #[derive(Debug)]
-pub(crate) struct PlonkProof {
+pub struct PlonkProof {
     pub(crate) lro: [Digest; 3],
     pub(crate) z: Digest,
     pub(crate) h: [Digest; 3],
@@ -9,3 +26,42 @@ pub(crate) struct PlonkProof {
     pub(crate) batched_proof: BatchOpeningProof,
     pub(crate) z_shifted_opening: OpeningProof,
 }
+
+impl PlonkProof {
+    /// Loads a proof from its gnark binary encoding, with uncompressed or compressed points.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, PlonkError> {
+        load_plonk_proof_from_bytes(buffer)
+    }
+
+    /// Writes the proof in the gnark binary encoding read by [`Self::from_bytes`], with
+    /// uncompressed points.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PlonkError> {
+        plonk_proof_to_bytes(self, PointEncoding::Uncompressed)
+    }
+
+    /// Writes the proof with the given point encoding, as gnark's `WriteTo` or `WriteRawTo` do.
+    pub fn to_bytes_with_encoding(&self, encoding: PointEncoding) -> Result<Vec<u8>, PlonkError> {
+        plonk_proof_to_bytes(self, encoding)
+    }
+
+    /// Loads a proof in the encoding of the gnark Solidity verifier, as submitted onchain.
+    ///
+    /// That encoding leaves out the opening of the linearized polynomial, which is recomputed from
+    /// the rest of the proof, so the verifying key and the public inputs are needed.
+    pub fn from_solidity_bytes(
+        buffer: &[u8],
+        vk: &PlonkVerifyingKey,
+        public_inputs: &[Fr],
+    ) -> Result<Self, PlonkError> {
+        let mut proof = load_plonk_proof_from_solidity_bytes(buffer, vk.qcp.len())?;
+        proof.batched_proof.claimed_values[0] =
+            linearized_polynomial_opening(vk, &proof, public_inputs)?;
+        Ok(proof)
+    }
+
+    /// Encodes the proof as the `bytes` proof argument of the gnark Solidity verifier, which leaves
+    /// out the opening of the linearized polynomial.
+    pub fn to_solidity_bytes(&self) -> Result<Vec<u8>, PlonkError> {
+        plonk_proof_to_solidity_bytes(self)
+    }
+}
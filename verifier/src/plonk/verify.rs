@@ -1,15 +1,25 @@
+use alloc::{string::ToString, vec, vec::Vec};
 use bn::{arith::U256, AffineG1, Fr};
 use core::hash::Hasher;
 
 use crate::{
     constants::{ALPHA, BETA, GAMMA, ZETA},
+    converter::{g1_to_bytes, KeyValidation, PointEncoding},
     error::Error,
+    kzg,
+    outcome::VerificationOutcome,
     transcript::Transcript,
 };
 
-use super::{converter::g1_to_bytes, error::PlonkError, kzg, PlonkProof};
+use super::{
+    converter::{load_plonk_verifying_key_from_bytes, plonk_verifying_key_to_bytes},
+    error::PlonkError,
+    PlonkProof,
+};
+
+/// A PlonK verifying key, as serialized by gnark.
 #[derive(Debug)]
-pub(crate) struct PlonkVerifyingKey {
+pub struct PlonkVerifyingKey {
     pub(crate) size: usize,
     pub(crate) size_inv: Fr,
     pub(crate) generator: Fr,
@@ -31,13 +41,33 @@ pub(crate) struct PlonkVerifyingKey {
     pub(crate) commitment_constraint_indexes: Vec<usize>,
 }
 
-use crate::wasm_bindgen;
+impl PlonkVerifyingKey {
+    /// Loads a verifying key from its gnark binary encoding, with compressed or uncompressed
+    /// points, checking that every point is valid.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self, PlonkError> {
+        load_plonk_verifying_key_from_bytes(buffer, KeyValidation::Strict)
+    }
+
+    /// Same as [`Self::from_bytes`], with the given validation of the points. Only skip it for
+    /// trusted keys.
+    pub fn from_bytes_with_validation(
+        buffer: &[u8],
+        validation: KeyValidation,
+    ) -> Result<Self, PlonkError> {
+        load_plonk_verifying_key_from_bytes(buffer, validation)
+    }
+
+    /// Writes the verifying key in the gnark binary encoding read by [`Self::from_bytes`], with
+    /// compressed points and all-zero KZG pairing lines.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, PlonkError> {
+        plonk_verifying_key_to_bytes(self, PointEncoding::Compressed)
+    }
 
-#[wasm_bindgen]
-/// Test
-extern {
-    #[wasm_bindgen(js_namespace = console)]
-    pub fn log(s: &str);
+    /// Writes the verifying key with the given point encoding, as gnark's `WriteTo` or
+    /// `WriteRawTo` do.
+    pub fn to_bytes_with_encoding(&self, encoding: PointEncoding) -> Result<Vec<u8>, PlonkError> {
+        plonk_verifying_key_to_bytes(self, encoding)
+    }
 }
 
 /// Verifies a PLONK proof
@@ -50,17 +80,130 @@ extern {
 ///
 /// # Returns
 ///
-/// * `Result<bool, PlonkError>` - Returns true if the proof is valid, or an error if verification fails
+/// * `VerificationOutcome` - `Valid` if the proof verifies, `Invalid` if the opening or pairing
+///   checks fail, or `Malformed` if the proof does not match the verifying key or public inputs
 pub fn verify_plonk(
     vk: &PlonkVerifyingKey,
     proof: &PlonkProof,
     public_inputs: &[Fr],
-) -> Result<bool, PlonkError> {
+) -> VerificationOutcome {
+    match verify_plonk_algebraic(vk, proof, public_inputs) {
+        Ok(()) => VerificationOutcome::Valid,
+        Err(e) => e.into(),
+    }
+}
+
+/// Verifies several PLONK proofs against the same verifying key
+///
+/// Each proof goes through its own transcript, linearization and folding, then the KZG opening claims of all the
+/// proofs are checked together with a single randomized pairing. If that check fails, the claims of each proof
+/// are checked on their own so the bad ones can be reported.
+///
+/// # Arguments
+///
+/// * `vk` - The verifying key
+/// * `proofs` - The PLONK proofs, each with its public inputs
+///
+/// # Returns
+///
+/// * `Vec<VerificationOutcome>` - The outcome of each proof, in the order they were given
+pub fn verify_plonk_batch(
+    vk: &PlonkVerifyingKey,
+    proofs: &[(&PlonkProof, &[Fr])],
+) -> Vec<VerificationOutcome> {
+    let mut outcomes = vec![VerificationOutcome::Valid; proofs.len()];
+
+    // Proofs failing before the pairing are reported directly and left out of the batch
+    let mut batch = Vec::with_capacity(proofs.len());
+    for (i, (proof, public_inputs)) in proofs.iter().enumerate() {
+        match compute_opening_claims(vk, proof, public_inputs) {
+            Ok(claims) => batch.push((i, claims)),
+            Err(e) => outcomes[i] = e.into(),
+        }
+    }
+
+    if batch.is_empty() {
+        return outcomes;
+    }
+
+    let mut digests = Vec::with_capacity(batch.len() * 2);
+    let mut opening_proofs = Vec::with_capacity(batch.len() * 2);
+    let mut points = Vec::with_capacity(batch.len() * 2);
+    for (_, claims) in batch.iter() {
+        digests.extend_from_slice(&claims.digests);
+        opening_proofs.extend_from_slice(&claims.proofs);
+        points.extend_from_slice(&claims.points);
+    }
+
+    if kzg::batch_verify_multi_points(&digests, &opening_proofs, &points, &vk.kzg).is_ok() {
+        return outcomes;
+    }
+
+    for (i, claims) in batch {
+        outcomes[i] = match claims.verify(&vk.kzg) {
+            Ok(()) => VerificationOutcome::Valid,
+            Err(e) => e.into(),
+        };
+    }
+
+    outcomes
+}
+
+// KZG opening claims of a proof, left to be checked with a pairing
+struct OpeningClaims {
+    digests: Vec<kzg::Digest>,
+    proofs: Vec<kzg::OpeningProof>,
+    points: Vec<Fr>,
+}
+
+impl OpeningClaims {
+    fn verify(&self, vk: &kzg::KZGVerifyingKey) -> Result<(), PlonkError> {
+        Ok(kzg::batch_verify_multi_points(
+            &self.digests,
+            &self.proofs,
+            &self.points,
+            vk,
+        )?)
+    }
+}
+
+fn verify_plonk_algebraic(
+    vk: &PlonkVerifyingKey,
+    proof: &PlonkProof,
+    public_inputs: &[Fr],
+) -> Result<(), PlonkError> {
+    compute_opening_claims(vk, proof, public_inputs)?.verify(&vk.kzg)
+}
+
+// Fiat-Shamir challenges of a proof, with the values derived from them that the rest of the verification needs
+struct Linearization {
+    gamma: Fr,
+    beta: Fr,
+    alpha: Fr,
+    zeta: Fr,
+    zh_zeta: Fr,
+    alpha_square_lagrange_one: Fr,
+    // Expected opening of the linearized polynomial at ζ
+    opening: Fr,
+}
+
+// Replay the transcript of a proof and compute the opening of its linearized polynomial at ζ, which only depends on
+// the commitments, the public inputs and the other claimed values
+fn compute_linearization(
+    vk: &PlonkVerifyingKey,
+    proof: &PlonkProof,
+    public_inputs: &[Fr],
+) -> Result<Linearization, PlonkError> {
     // Check if the number of BSB22 commitments matches the number of Qcp in the verifying key
     if proof.bsb22_commitments.len() != vk.qcp.len() {
         return Err(PlonkError::GeneralError(Error::Bsb22CommitmentMismatch));
     }
 
+    // Check if the proof claims the openings of the linearized polynomial, l, r, o, s1, s2 and of every Qcp
+    if proof.batched_proof.claimed_values.len() != 6 + vk.qcp.len() {
+        return Err(PlonkError::GeneralError(Error::InvalidNumberOfDigests));
+    }
+
     // Check if the number of public inputs matches the number of public variables in the verifying key
     if public_inputs.len() != vk.nb_public_variables {
         return Err(PlonkError::GeneralError(Error::InvalidWitness));
@@ -147,15 +290,22 @@ pub fn verify_plonk(
     // Handle BSB22 commitments
     let mut hash_to_field = crate::hash_to_field::WrappedHashToField::new(b"BSB22-Plonk")?;
 
-    for i in 0..vk.commitment_constraint_indexes.len() {
-        hash_to_field.write(&g1_to_bytes(&proof.bsb22_commitments[i])?);
+    for (commitment, index) in proof
+        .bsb22_commitments
+        .iter()
+        .zip(&vk.commitment_constraint_indexes)
+    {
+        hash_to_field.write(&g1_to_bytes(commitment)?);
         let hash_bts = hash_to_field.sum()?;
         hash_to_field.reset();
         let hashed_cmt = Fr::from_bytes_be_mod_order(&hash_bts)
             .map_err(|_| Error::FailedToGetFrFromRandomBytes)?;
 
-        let exponent =
-            U256::from((vk.nb_public_variables + vk.commitment_constraint_indexes[i]) as u64);
+        let exponent = vk
+            .nb_public_variables
+            .checked_add(*index)
+            .ok_or(Error::BeyondTheModulus)?;
+        let exponent = U256::from(exponent as u64);
         let exponent = Fr::new(exponent).ok_or(Error::BeyondTheModulus)?;
         let w_pow_i = vk.generator.pow(exponent);
         let mut den = zeta;
@@ -214,13 +364,58 @@ pub fn verify_plonk(
 
     const_lin = -const_lin;
 
+    Ok(Linearization {
+        gamma,
+        beta,
+        alpha,
+        zeta,
+        zh_zeta,
+        alpha_square_lagrange_one,
+        opening: const_lin,
+    })
+}
+
+/// Computes the opening at ζ of the linearized polynomial of a proof, which the onchain encoding of PlonK proofs
+/// leaves out.
+pub(crate) fn linearized_polynomial_opening(
+    vk: &PlonkVerifyingKey,
+    proof: &PlonkProof,
+    public_inputs: &[Fr],
+) -> Result<Fr, PlonkError> {
+    Ok(compute_linearization(vk, proof, public_inputs)?.opening)
+}
+
+// Run every check of a proof up to the final pairing, and return the opening claims that remain to be checked
+fn compute_opening_claims(
+    vk: &PlonkVerifyingKey,
+    proof: &PlonkProof,
+    public_inputs: &[Fr],
+) -> Result<OpeningClaims, PlonkError> {
+    let Linearization {
+        gamma,
+        beta,
+        alpha,
+        zeta,
+        zh_zeta,
+        alpha_square_lagrange_one,
+        opening,
+    } = compute_linearization(vk, proof, public_inputs)?;
+
     // Check if the opening of the linearized polynomial is equal to -const_lin
     let opening_lin_pol = proof.batched_proof.claimed_values[0];
 
-    if const_lin != opening_lin_pol {
+    if opening != opening_lin_pol {
         return Err(Error::OpeningPolyMismatch.into());
     }
 
+    let one = Fr::one();
+    let l = proof.batched_proof.claimed_values[1];
+    let r = proof.batched_proof.claimed_values[2];
+    let o = proof.batched_proof.claimed_values[3];
+    let s1 = proof.batched_proof.claimed_values[4];
+    let s2 = proof.batched_proof.claimed_values[5];
+    let zu = proof.z_shifted_opening.claimed_value;
+
     // Compute coefficients for the linearized polynomial
     // _s1 = α*(l(ζ)+β*s1(ζ)+γ)*(r(ζ)+β*s2(ζ)+γ)*β*Z(ωζ)
     let mut _s1 = beta * s1 + l + gamma;
@@ -303,7 +498,7 @@ pub fn verify_plonk(
 
     // Fold the proof
     let (folded_proof, folded_digest) = kzg::fold_proof(
-        digests_to_fold,
+        &digests_to_fold,
         &proof.batched_proof,
         &zeta,
         Some(zu.into_u256().to_bytes_be().to_vec()),
@@ -313,15 +508,25 @@ pub fn verify_plonk(
 
     let folded_digest: AffineG1 = folded_digest;
 
-    // Perform batch verification
-    kzg::batch_verify_multi_points(
-        [folded_digest, proof.z].to_vec(),
-        [folded_proof, proof.z_shifted_opening].to_vec(),
-        [zeta, shifted_zeta].to_vec(),
-        &vk.kzg,
-    )?;
+    Ok(OpeningClaims {
+        digests: [folded_digest, proof.z].to_vec(),
+        proofs: [folded_proof, proof.z_shifted_opening].to_vec(),
+        points: [zeta, shifted_zeta].to_vec(),
+    })
+}
 
-    Ok(true)
+impl From<PlonkError> for VerificationOutcome {
+    fn from(error: PlonkError) -> Self {
+        match error {
+            PlonkError::OpeningPolyMismatch
+            | PlonkError::PairingCheckFailed
+            | PlonkError::GeneralError(Error::OpeningPolyMismatch)
+            | PlonkError::GeneralError(Error::PairingCheckFailed) => {
+                VerificationOutcome::invalid(error)
+            }
+            _ => VerificationOutcome::malformed(error),
+        }
+    }
 }
 
 fn bind_public_data(
@@ -0,0 +1,157 @@
+use alloc::boxed::Box;
+use bn::{AffineG1, AffineG2, Fr, G1};
+
+use crate::{
+    converter::{fr_from_be_bytes, KeyValidation, PointEncoding},
+    error::Error,
+};
+
+// Cursor over gnark's binary encoding: big-endian integers and scalars, and points in the encoding of
+// the first one. Each read names the field it reads, so that truncated, inconsistent or undecodable
+// input is reported with the field and its offset instead of indexing out of bounds.
+pub(crate) struct Reader<'a> {
+    buffer: &'a [u8],
+    offset: usize,
+    encoding: PointEncoding,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buffer: &'a [u8], encoding: PointEncoding) -> Self {
+        Reader {
+            buffer,
+            offset: 0,
+            encoding,
+        }
+    }
+
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.offset == self.buffer.len()
+    }
+
+    pub(crate) fn read_bytes(
+        &mut self,
+        len: usize,
+        field: &'static str,
+    ) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .offset
+            .checked_add(len)
+            .and_then(|end| self.buffer.get(self.offset..end))
+            .ok_or(Error::InsufficientBufferLength {
+                field,
+                offset: self.offset,
+                expected: self.offset.saturating_add(len),
+                got: self.buffer.len(),
+            })?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    pub(crate) fn read_u32(&mut self, field: &'static str) -> Result<u32, Error> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.read_bytes(4, field)?);
+        Ok(u32::from_be_bytes(buf))
+    }
+
+    pub(crate) fn read_u64(&mut self, field: &'static str) -> Result<u64, Error> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.read_bytes(8, field)?);
+        Ok(u64::from_be_bytes(buf))
+    }
+
+    // Reads a uint64 that must fit in a usize
+    pub(crate) fn read_u64_as_usize(&mut self, field: &'static str) -> Result<usize, Error> {
+        let offset = self.offset;
+        usize::try_from(self.read_u64(field)?).map_err(|_| Error::InvalidField { field, offset })
+    }
+
+    // Reads the uint32 length prefix of a slice
+    pub(crate) fn read_len(&mut self, field: &'static str) -> Result<usize, Error> {
+        Ok(self.read_u32(field)? as usize)
+    }
+
+    // Reads the next `len` bytes and decodes them, reporting decoding errors with the field and
+    // its offset
+    fn decode<T>(
+        &mut self,
+        len: usize,
+        field: &'static str,
+        decode: impl FnOnce(&[u8]) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        let offset = self.offset;
+        decode(self.read_bytes(len, field)?).map_err(|e| Error::InvalidFieldEncoding {
+            field,
+            offset,
+            source: Box::new(e),
+        })
+    }
+
+    pub(crate) fn read_fr(&mut self, field: &'static str) -> Result<Fr, Error> {
+        self.decode(32, field, fr_from_be_bytes)
+    }
+
+    pub(crate) fn read_g1(&mut self, field: &'static str) -> Result<AffineG1, Error> {
+        let encoding = self.encoding;
+        self.decode(encoding.g1_len(), field, |buf| encoding.read_g1(buf))
+    }
+
+    pub(crate) fn read_g1_or_zero(&mut self, field: &'static str) -> Result<G1, Error> {
+        let encoding = self.encoding;
+        self.decode(encoding.g1_len(), field, |buf| {
+            encoding.read_g1_or_zero(buf)
+        })
+    }
+
+    pub(crate) fn read_g2(&mut self, field: &'static str) -> Result<AffineG2, Error> {
+        let encoding = self.encoding;
+        self.decode(encoding.g2_len(), field, |buf| encoding.read_g2(buf))
+    }
+
+    pub(crate) fn read_key_g1(
+        &mut self,
+        field: &'static str,
+        validation: KeyValidation,
+    ) -> Result<AffineG1, Error> {
+        let encoding = self.encoding;
+        self.decode(encoding.g1_len(), field, |buf| {
+            encoding.read_key_g1(buf, validation)
+        })
+    }
+
+    pub(crate) fn read_key_g1_or_zero(
+        &mut self,
+        field: &'static str,
+        validation: KeyValidation,
+    ) -> Result<AffineG1, Error> {
+        let encoding = self.encoding;
+        self.decode(encoding.g1_len(), field, |buf| {
+            encoding.read_key_g1_or_zero(buf, validation)
+        })
+    }
+
+    pub(crate) fn read_key_g2(
+        &mut self,
+        field: &'static str,
+        validation: KeyValidation,
+    ) -> Result<AffineG2, Error> {
+        let encoding = self.encoding;
+        self.decode(encoding.g2_len(), field, |buf| {
+            encoding.read_key_g2(buf, validation)
+        })
+    }
+
+    // Rejects anything left after the last field
+    pub(crate) fn finish(&self, field: &'static str) -> Result<(), Error> {
+        if !self.is_empty() {
+            return Err(Error::TrailingBytes {
+                field,
+                offset: self.offset,
+            });
+        }
+        Ok(())
+    }
+}
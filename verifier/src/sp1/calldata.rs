@@ -0,0 +1,151 @@
+use alloc::{format, string::String, vec::Vec};
+use bn::Fr;
+
+use crate::converter::fr_from_be_bytes;
+
+use super::{committed_values_digest, error::SP1Error, SP1CircuitKey, SP1ProofBundle};
+
+/// Function selector of `verifyProof(bytes32,bytes,bytes)` on the SP1 verifier gateway.
+pub const VERIFY_PROOF_SELECTOR: [u8; 4] = [0x41, 0x49, 0x3c, 0x60];
+
+/// The arguments of a `verifyProof(bytes32 programVKey, bytes publicValues, bytes proofBytes)` call
+/// to the SP1 verifier gateway, decoded from its calldata.
+#[derive(Debug, Clone)]
+pub struct SP1Calldata {
+    /// The program verifying key hash, as given by `SP1VerifyingKey::bytes32`.
+    pub program_vkey: [u8; 32],
+    /// The public values committed by the program.
+    pub public_values: Vec<u8>,
+    /// The first 4 bytes of the hash of the circuit verifying key the gateway routes the proof to.
+    pub verifier_selector: [u8; 4],
+    /// The PlonK or Groth16 proof, in the encoding of the gnark Solidity verifiers.
+    pub proof: Vec<u8>,
+}
+
+impl SP1Calldata {
+    /// Decodes the calldata of a `verifyProof` call, starting with its function selector.
+    pub fn from_bytes(calldata: &[u8]) -> Result<Self, SP1Error> {
+        let args = calldata
+            .strip_prefix(&VERIFY_PROOF_SELECTOR)
+            .ok_or(SP1Error::UnexpectedFunctionSelector)?;
+
+        let mut program_vkey = [0u8; 32];
+        program_vkey.copy_from_slice(word(args, 0)?);
+        let public_values = dynamic_bytes(args, 32)?;
+        let proof_bytes = dynamic_bytes(args, 64)?;
+
+        if proof_bytes.len() < 4 {
+            return Err(SP1Error::InvalidCalldata);
+        }
+        let (verifier_selector, proof) = proof_bytes.split_at(4);
+
+        Ok(SP1Calldata {
+            program_vkey,
+            public_values: public_values.to_vec(),
+            verifier_selector: verifier_selector.try_into().expect("4 bytes"),
+            proof: proof.to_vec(),
+        })
+    }
+
+    /// Decodes hex calldata, with or without a `0x` prefix, as shown by block explorers.
+    pub fn from_hex(calldata: &str) -> Result<Self, SP1Error> {
+        let calldata = calldata.trim();
+        let calldata = hex::decode(calldata.strip_prefix("0x").unwrap_or(calldata))
+            .map_err(|_| SP1Error::InvalidHex)?;
+        Self::from_bytes(&calldata)
+    }
+
+    /// Builds the `verifyProof` call submitting the proof of an SP1 proof bundle onchain.
+    pub fn from_bundle(bundle: &SP1ProofBundle) -> Self {
+        let mut verifier_selector = [0u8; 4];
+        verifier_selector.copy_from_slice(&bundle.circuit_vkey_hash[..4]);
+
+        SP1Calldata {
+            program_vkey: bundle.public_inputs[0].into_u256().to_bytes_be(),
+            public_values: bundle.public_values.clone(),
+            verifier_selector,
+            proof: bundle.encoded_proof.clone(),
+        }
+    }
+
+    /// Encodes the `verifyProof` call, starting with its function selector.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let public_values = abi_encode_bytes(&[&self.public_values]);
+        let proof_bytes = abi_encode_bytes(&[&self.verifier_selector, &self.proof]);
+
+        let mut calldata = VERIFY_PROOF_SELECTOR.to_vec();
+        calldata.extend_from_slice(&self.program_vkey);
+        // Heads of the two dynamic arguments, pointing past the three head words
+        calldata.extend_from_slice(&abi_word(96));
+        calldata.extend_from_slice(&abi_word(96 + public_values.len()));
+        calldata.extend_from_slice(&public_values);
+        calldata.extend_from_slice(&proof_bytes);
+        calldata
+    }
+
+    /// Encodes the `verifyProof` call as `0x`-prefixed hex, to be used as transaction input.
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.to_bytes()))
+    }
+
+    /// Finds the embedded circuit verifying key the verifier selector routes the proof to.
+    pub fn circuit_key(&self) -> Result<&'static SP1CircuitKey, SP1Error> {
+        SP1CircuitKey::by_selector(&self.verifier_selector)
+            .ok_or_else(|| SP1Error::UnknownVerifierSelector(hex::encode(self.verifier_selector)))
+    }
+
+    /// Rebuilds the public inputs of the proof: the program verifying key hash and the committed
+    /// values digest.
+    pub fn public_inputs(&self) -> Result<[Fr; 2], SP1Error> {
+        Ok([
+            fr_from_be_bytes(&self.program_vkey).map_err(|_| SP1Error::InvalidProgramVkeyHash)?,
+            committed_values_digest(&self.public_values),
+        ])
+    }
+}
+
+fn abi_word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+// Encodes the concatenation of `parts` as the length-prefixed, zero-padded contents of a `bytes`
+// argument
+fn abi_encode_bytes(parts: &[&[u8]]) -> Vec<u8> {
+    let len = parts.iter().map(|part| part.len()).sum();
+    let mut encoded = abi_word(len).to_vec();
+    for part in parts {
+        encoded.extend_from_slice(part);
+    }
+    encoded.resize(32 + len.div_ceil(32) * 32, 0);
+    encoded
+}
+
+// Reads the 32-byte ABI word at `offset`
+fn word(args: &[u8], offset: usize) -> Result<&[u8], SP1Error> {
+    args.get(offset..offset + 32)
+        .ok_or(SP1Error::InvalidCalldata)
+}
+
+// Reads a word holding an offset or a length, which must fit in a usize
+fn word_as_usize(args: &[u8], offset: usize) -> Result<usize, SP1Error> {
+    let word = word(args, offset)?;
+    let (high, low) = word.split_at(24);
+    if high.iter().any(|byte| *byte != 0) {
+        return Err(SP1Error::InvalidCalldata);
+    }
+    usize::try_from(u64::from_be_bytes(low.try_into().expect("8 bytes")))
+        .map_err(|_| SP1Error::InvalidCalldata)
+}
+
+// Reads a `bytes` argument, whose head at `offset` holds the position of its length-prefixed
+// contents in the arguments
+fn dynamic_bytes(args: &[u8], offset: usize) -> Result<&[u8], SP1Error> {
+    let start = word_as_usize(args, offset)?;
+    let len = word_as_usize(args, start)?;
+    start
+        .checked_add(32)
+        .and_then(|data| args.get(data..data.checked_add(len)?))
+        .ok_or(SP1Error::InvalidCalldata)
+}
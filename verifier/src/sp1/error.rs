@@ -0,0 +1,41 @@
+use alloc::string::String;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SP1Error {
+    #[error("Unexpected end of SP1 proof bundle")]
+    UnexpectedEnd,
+    #[error("Trailing bytes after SP1 proof bundle")]
+    TrailingBytes,
+    #[error("Unsupported SP1 proof variant {0}, expected Plonk or Groth16")]
+    UnsupportedProofVariant(u32),
+    #[error("SP1 proof bundles carrying stdin proofs are not supported")]
+    UnsupportedStdinProofs,
+    #[error("Expected a {expected:?} proof, got a {got:?} proof")]
+    ProofKindMismatch {
+        expected: super::SP1ProofKind,
+        got: super::SP1ProofKind,
+    },
+    #[error("No embedded SP1 circuit verifying key matches this {0} proof")]
+    UnknownCircuitKey(String),
+    #[error("Invalid proof JSON: {0}")]
+    InvalidJson(String),
+    #[error("Public inputs do not match the vkey_hash and committed_values_digest fields")]
+    JsonPublicInputsMismatch,
+    #[error("Invalid UTF-8 string")]
+    InvalidUtf8,
+    #[error("Invalid hex string")]
+    InvalidHex,
+    #[error("Public values do not match the committed values digest")]
+    PublicValuesDigestMismatch,
+    #[error("Invalid program verifying key hash, expected a 0x-prefixed bytes32 BN254 scalar")]
+    InvalidProgramVkeyHash,
+    #[error("Invalid public input: {0}")]
+    InvalidPublicInput(String),
+    #[error("Calldata is not a verifyProof(bytes32,bytes,bytes) call")]
+    UnexpectedFunctionSelector,
+    #[error("Invalid ABI encoding of the verifyProof arguments")]
+    InvalidCalldata,
+    #[error("No embedded SP1 circuit verifying key matches the verifier selector 0x{0}")]
+    UnknownVerifierSelector(String),
+}
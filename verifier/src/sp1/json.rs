@@ -0,0 +1,102 @@
+use alloc::{format, string::String, vec::Vec};
+use bn::Fr;
+use serde_json::Value;
+
+use crate::converter::fr_from_str;
+
+use super::{error::SP1Error, SP1CircuitKey, SP1ProofKind};
+
+/// A proof in the JSON format written next to SP1 proof bundles by the example script
+/// (`*.bin.json`).
+///
+/// Only the gnark proof, its public inputs and the optional `circuit_vkey_hash` are kept; the
+/// `vkey_hash` and `committed_values_digest` fields must match the public inputs.
+#[derive(Debug, Clone)]
+pub struct SP1ProofJson {
+    /// The proof as serialized by gnark.
+    pub raw_proof: Vec<u8>,
+    /// The program verifying key hash and the committed values digest.
+    pub public_inputs: [Fr; 2],
+    /// The SHA-256 hash of the circuit verifying key the proof was generated for, if the JSON has
+    /// one. Files written by earlier versions of the example script do not.
+    pub circuit_vkey_hash: Option<[u8; 32]>,
+}
+
+impl SP1ProofJson {
+    /// Parses a proof from its JSON encoding.
+    pub fn from_json(json: &str) -> Result<Self, SP1Error> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| SP1Error::InvalidJson(format!("{}", e)))?;
+
+        let raw_proof = hex::decode(str_field(&value, "raw_proof")?.trim_start_matches("0x"))
+            .map_err(|_| SP1Error::InvalidHex)?;
+
+        let public_inputs = match value.get("public_inputs").and_then(Value::as_array) {
+            Some(inputs) if inputs.len() == 2 => [
+                parse_public_input(&inputs[0])?,
+                parse_public_input(&inputs[1])?,
+            ],
+            _ => {
+                return Err(SP1Error::InvalidJson(String::from(
+                    "public_inputs must be an array of two decimal strings",
+                )))
+            }
+        };
+
+        // Both are big-endian hex, written without a 0x prefix and leading zeros may be dropped
+        let vkey_hash = hex_field_to_fr(&value, "vkey_hash")?;
+        let committed_values_digest = hex_field_to_fr(&value, "committed_values_digest")?;
+        if vkey_hash != public_inputs[0] || committed_values_digest != public_inputs[1] {
+            return Err(SP1Error::JsonPublicInputsMismatch);
+        }
+
+        let circuit_vkey_hash = match value.get("circuit_vkey_hash") {
+            Some(_) => {
+                let mut hash = [0u8; 32];
+                hex::decode_to_slice(
+                    str_field(&value, "circuit_vkey_hash")?.trim_start_matches("0x"),
+                    &mut hash,
+                )
+                .map_err(|_| SP1Error::InvalidHex)?;
+                Some(hash)
+            }
+            None => None,
+        };
+
+        Ok(SP1ProofJson {
+            raw_proof,
+            public_inputs,
+            circuit_vkey_hash,
+        })
+    }
+
+    /// Finds the embedded circuit verifying key the proof was generated for, by its hash, or else
+    /// the one of the most recent SP1 version.
+    pub fn circuit_key(&self, kind: SP1ProofKind) -> Result<&'static SP1CircuitKey, SP1Error> {
+        match &self.circuit_vkey_hash {
+            Some(hash) => SP1CircuitKey::by_hash_prefix(kind, hash)
+                .ok_or_else(|| SP1Error::UnknownVerifierSelector(hex::encode(&hash[..4]))),
+            None => Ok(SP1CircuitKey::latest(kind)),
+        }
+    }
+}
+
+fn str_field<'a>(value: &'a Value, name: &str) -> Result<&'a str, SP1Error> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| SP1Error::InvalidJson(format!("missing string field {}", name)))
+}
+
+fn hex_field_to_fr(value: &Value, name: &str) -> Result<Fr, SP1Error> {
+    let field = str_field(value, name)?;
+    fr_from_str(&format!("0x{}", field.trim_start_matches("0x")))
+        .map_err(|_| SP1Error::InvalidJson(format!("invalid {}: {}", name, field)))
+}
+
+fn parse_public_input(value: &Value) -> Result<Fr, SP1Error> {
+    let input = value
+        .as_str()
+        .ok_or_else(|| SP1Error::InvalidJson(String::from("public inputs must be strings")))?;
+    fr_from_str(input).map_err(|_| SP1Error::InvalidPublicInput(String::from(input)))
+}
@@ -0,0 +1,14 @@
+mod calldata;
+mod json;
+mod proof;
+mod registry;
+
+pub(crate) mod error;
+
+pub use calldata::{SP1Calldata, VERIFY_PROOF_SELECTOR};
+pub use json::SP1ProofJson;
+pub use proof::{
+    committed_values_digest, program_vkey_hash_to_fr, sp1_public_inputs, SP1ProofBundle,
+    SP1ProofKind,
+};
+pub use registry::SP1CircuitKey;
@@ -0,0 +1,190 @@
+use alloc::{string::String, vec::Vec};
+use bn::Fr;
+use sha2::{Digest, Sha256};
+
+use crate::converter::{fr_from_be_bytes, fr_from_str};
+
+use super::{error::SP1Error, SP1CircuitKey};
+
+// Variant indexes of `SP1Proof` in the bincode encoding of SP1ProofWithPublicValues
+const PLONK_VARIANT: u32 = 2;
+const GROTH16_VARIANT: u32 = 3;
+
+/// The kind of gnark proof wrapped in an SP1 proof bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SP1ProofKind {
+    Plonk,
+    Groth16,
+}
+
+/// A PlonK or Groth16 proof read from a bincode-serialized `SP1ProofWithPublicValues`.
+///
+/// Only the fields needed for verification are kept; the decoder does not depend on `sp1-sdk`.
+#[derive(Debug, Clone)]
+pub struct SP1ProofBundle {
+    /// Whether the bundle wraps a PlonK or a Groth16 proof.
+    pub kind: SP1ProofKind,
+    /// The SP1 verifying key hash and the committed values digest.
+    pub public_inputs: [Fr; 2],
+    /// The proof in the encoding used by the onchain verifiers.
+    pub encoded_proof: Vec<u8>,
+    /// The proof as serialized by gnark, as read by [`crate::PlonkProof::from_bytes`] or
+    /// [`crate::Groth16Proof::from_bytes`].
+    pub raw_proof: Vec<u8>,
+    /// The public values committed by the program.
+    pub public_values: Vec<u8>,
+    /// The SHA-256 hash of the circuit verifying key the proof was generated for.
+    pub circuit_vkey_hash: [u8; 32],
+    /// The SP1 version the proof was generated with, e.g. "v2.0.0".
+    pub sp1_version: String,
+}
+
+impl SP1ProofBundle {
+    /// Decodes a bundle as saved by `SP1ProofWithPublicValues::save`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SP1Error> {
+        let mut reader = Reader { bytes };
+
+        let kind = match reader.read_u32()? {
+            PLONK_VARIANT => SP1ProofKind::Plonk,
+            GROTH16_VARIANT => SP1ProofKind::Groth16,
+            variant => return Err(SP1Error::UnsupportedProofVariant(variant)),
+        };
+
+        // Both PlonkBn254Proof and Groth16Bn254Proof share the same layout
+        let public_inputs = [
+            parse_public_input(reader.read_string()?)?,
+            parse_public_input(reader.read_string()?)?,
+        ];
+        let encoded_proof = decode_hex(reader.read_string()?)?;
+        let raw_proof = decode_hex(reader.read_string()?)?;
+        let mut circuit_vkey_hash = [0u8; 32];
+        circuit_vkey_hash.copy_from_slice(reader.read_bytes(32)?);
+
+        // SP1Stdin: buffer, ptr and proofs
+        for _ in 0..reader.read_u64()? {
+            reader.read_vec()?;
+        }
+        reader.read_u64()?;
+        if reader.read_u64()? != 0 {
+            return Err(SP1Error::UnsupportedStdinProofs);
+        }
+
+        let public_values = reader.read_vec()?.to_vec();
+        let sp1_version = String::from(reader.read_string()?);
+
+        if !reader.bytes.is_empty() {
+            return Err(SP1Error::TrailingBytes);
+        }
+
+        Ok(SP1ProofBundle {
+            kind,
+            public_inputs,
+            encoded_proof,
+            raw_proof,
+            public_values,
+            circuit_vkey_hash,
+            sp1_version,
+        })
+    }
+
+    /// The proof as `SP1ProofWithPublicValues::bytes` returns it and
+    /// [`crate::SP1Verifier::verify_onchain`] reads it: the 4-byte verifier selector followed by the encoded proof.
+    pub fn bytes(&self) -> Vec<u8> {
+        [&self.circuit_vkey_hash[..4], &self.encoded_proof[..]].concat()
+    }
+
+    /// Finds the embedded circuit verifying key the proof was generated for, by its hash or else by
+    /// the SP1 version of the bundle.
+    pub fn circuit_key(&self) -> Result<&'static SP1CircuitKey, SP1Error> {
+        SP1CircuitKey::by_hash_prefix(self.kind, &self.circuit_vkey_hash)
+            .or_else(|| SP1CircuitKey::by_version(self.kind, &self.sp1_version))
+            .ok_or_else(|| SP1Error::UnknownCircuitKey(self.sp1_version.clone()))
+    }
+
+    /// Checks that the public values hash to the committed values digest, the second public input.
+    ///
+    /// The proof only attests to the digest, so the public values must not be trusted without this
+    /// check.
+    pub fn check_public_values(&self) -> Result<(), SP1Error> {
+        if committed_values_digest(&self.public_values) == self.public_inputs[1] {
+            Ok(())
+        } else {
+            Err(SP1Error::PublicValuesDigestMismatch)
+        }
+    }
+}
+
+/// Computes the digest SP1 commits to for the given public values.
+///
+/// This is the SHA-256 hash of the public values with its top three bits cleared, so that it fits
+/// in a BN254 scalar.
+pub fn committed_values_digest(public_values: &[u8]) -> Fr {
+    let mut digest: [u8; 32] = Sha256::digest(public_values).into();
+    digest[0] &= 0x1f;
+    Fr::from_slice(&digest).expect("A 253-bit digest is below the scalar field modulus")
+}
+
+// Reads the bincode default encoding: little-endian integers and u64 length prefixes
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], SP1Error> {
+        if self.bytes.len() < len {
+            return Err(SP1Error::UnexpectedEnd);
+        }
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, SP1Error> {
+        let mut buf = [0u8; 4];
+        buf.copy_from_slice(self.read_bytes(4)?);
+        Ok(u32::from_le_bytes(buf))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, SP1Error> {
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(self.read_bytes(8)?);
+        Ok(u64::from_le_bytes(buf))
+    }
+
+    fn read_vec(&mut self) -> Result<&'a [u8], SP1Error> {
+        let len = usize::try_from(self.read_u64()?).map_err(|_| SP1Error::UnexpectedEnd)?;
+        self.read_bytes(len)
+    }
+
+    fn read_string(&mut self) -> Result<&'a str, SP1Error> {
+        core::str::from_utf8(self.read_vec()?).map_err(|_| SP1Error::InvalidUtf8)
+    }
+}
+
+/// Converts a program verifying key hash, as given by `SP1VerifyingKey::bytes32`, to a scalar.
+///
+/// The hash is a `0x`-prefixed big-endian encoding of a BN254 scalar.
+pub fn program_vkey_hash_to_fr(program_vkey_hash: &str) -> Result<Fr, SP1Error> {
+    let bytes = decode_hex(program_vkey_hash).map_err(|_| SP1Error::InvalidProgramVkeyHash)?;
+    fr_from_be_bytes(&bytes).map_err(|_| SP1Error::InvalidProgramVkeyHash)
+}
+
+/// Rebuilds the public inputs of the PlonK or Groth16 proof wrapping an SP1 proof: the program
+/// verifying key hash and the committed values digest.
+pub fn sp1_public_inputs(
+    program_vkey_hash: &str,
+    public_values: &[u8],
+) -> Result<[Fr; 2], SP1Error> {
+    Ok([
+        program_vkey_hash_to_fr(program_vkey_hash)?,
+        committed_values_digest(public_values),
+    ])
+}
+
+fn decode_hex(s: &str) -> Result<Vec<u8>, SP1Error> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(|_| SP1Error::InvalidHex)
+}
+
+fn parse_public_input(s: &str) -> Result<Fr, SP1Error> {
+    fr_from_str(s).map_err(|_| SP1Error::InvalidPublicInput(String::from(s)))
+}
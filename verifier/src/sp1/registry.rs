@@ -0,0 +1,76 @@
+use sha2::{Digest, Sha256};
+
+use super::SP1ProofKind;
+
+/// A verifying key of the SP1 PlonK or Groth16 wrapping circuit, as shipped with an SP1 release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SP1CircuitKey {
+    /// The SP1 circuit version the key belongs to, e.g. "v2.0.0".
+    pub version: &'static str,
+    /// Whether this is the PlonK or the Groth16 key.
+    pub kind: SP1ProofKind,
+    /// The gnark-serialized verifying key.
+    pub bytes: &'static [u8],
+}
+
+// Oldest first. To support a new SP1 release, copy its keys to vk/<version>/ with vk/circuits and
+// append them here.
+static SP1_CIRCUIT_KEYS: &[SP1CircuitKey] = &[
+    SP1CircuitKey {
+        version: "v2.0.0",
+        kind: SP1ProofKind::Plonk,
+        bytes: include_bytes!("../../../vk/v2.0.0/plonk_vk.bin"),
+    },
+    SP1CircuitKey {
+        version: "v2.0.0",
+        kind: SP1ProofKind::Groth16,
+        bytes: include_bytes!("../../../vk/v2.0.0/groth16_vk.bin"),
+    },
+];
+
+impl SP1CircuitKey {
+    /// All embedded keys, oldest SP1 version first.
+    pub fn all() -> &'static [SP1CircuitKey] {
+        SP1_CIRCUIT_KEYS
+    }
+
+    /// The key of the most recent embedded SP1 version.
+    pub fn latest(kind: SP1ProofKind) -> &'static SP1CircuitKey {
+        SP1_CIRCUIT_KEYS
+            .iter()
+            .rev()
+            .find(|key| key.kind == kind)
+            .expect("Both a PlonK and a Groth16 key are embedded")
+    }
+
+    /// Finds the key of the given SP1 circuit version, e.g. "v2.0.0".
+    pub fn by_version(kind: SP1ProofKind, version: &str) -> Option<&'static SP1CircuitKey> {
+        SP1_CIRCUIT_KEYS
+            .iter()
+            .find(|key| key.kind == kind && key.version == version)
+    }
+
+    /// Finds the key whose hash starts with `prefix`, such as the 4-byte selector prefixing onchain
+    /// proofs or the full 32-byte hash stored in an SP1 proof bundle.
+    pub fn by_hash_prefix(kind: SP1ProofKind, prefix: &[u8]) -> Option<&'static SP1CircuitKey> {
+        if prefix.is_empty() {
+            return None;
+        }
+        SP1_CIRCUIT_KEYS
+            .iter()
+            .find(|key| key.kind == kind && key.hash().starts_with(prefix))
+    }
+
+    /// Finds the PlonK or Groth16 key selected by the 4-byte prefix of an onchain proof, which the
+    /// SP1 verifier gateway routes on.
+    pub fn by_selector(selector: &[u8; 4]) -> Option<&'static SP1CircuitKey> {
+        SP1_CIRCUIT_KEYS
+            .iter()
+            .find(|key| key.hash().starts_with(selector))
+    }
+
+    /// The SHA-256 hash of the key, which SP1 uses to identify it.
+    pub fn hash(&self) -> [u8; 32] {
+        Sha256::digest(self.bytes).into()
+    }
+}
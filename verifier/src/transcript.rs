@@ -1,5 +1,6 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+use bn::Fr;
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
 
 use crate::error::Error;
 
@@ -15,7 +16,7 @@ pub(crate) struct Challenge {
 pub(crate) struct Transcript {
     pub(crate) h: Sha256,
 
-    pub(crate) challenges: HashMap<String, Challenge>,
+    pub(crate) challenges: BTreeMap<String, Challenge>,
     previous_challenge: Option<Challenge>,
 }
 
@@ -24,7 +25,7 @@ impl Transcript {
         let h = Sha256::new();
 
         if let Some(challenges_id) = challenges_id {
-            let mut challenges = HashMap::new();
+            let mut challenges = BTreeMap::new();
             for (position, id) in challenges_id.iter().enumerate() {
                 challenges.insert(
                     id.clone(),
@@ -45,7 +46,7 @@ impl Transcript {
         } else {
             Ok(Transcript {
                 h,
-                challenges: HashMap::new(),
+                challenges: BTreeMap::new(),
                 previous_challenge: None,
             })
         }
@@ -106,3 +107,51 @@ impl Transcript {
         Ok(res.to_vec())
     }
 }
+
+/// Derive the `nb` coefficients combining the checks of a batch, the first one always being 1.
+/// `data` returns every input of the batch, so that no proof can be chosen after the coefficients.
+///
+/// Drawn from the OS RNG, without reading `data`.
+#[cfg(feature = "rng")]
+pub(crate) fn batch_coefficients(
+    nb: usize,
+    _data: impl FnOnce() -> Result<Vec<u8>, Error>,
+) -> Result<Vec<Fr>, Error> {
+    let mut rng = rand::rngs::OsRng;
+    let mut coefficients = Vec::with_capacity(nb);
+    if nb > 0 {
+        coefficients.push(Fr::one());
+    }
+    for _ in 1..nb {
+        coefficients.push(Fr::random(&mut rng));
+    }
+
+    Ok(coefficients)
+}
+
+/// Derive the `nb` coefficients combining the checks of a batch, the first one always being 1.
+/// `data` returns every input of the batch, so that no proof can be chosen after the coefficients.
+///
+/// Derived as the powers of a challenge bound to everything `data` returns.
+#[cfg(not(feature = "rng"))]
+pub(crate) fn batch_coefficients(
+    nb: usize,
+    data: impl FnOnce() -> Result<Vec<u8>, Error>,
+) -> Result<Vec<Fr>, Error> {
+    use crate::constants::BATCH_CHALLENGE;
+    use alloc::string::ToString;
+
+    let mut transcript = Transcript::new(Some([BATCH_CHALLENGE.to_string()].to_vec()))?;
+    transcript.bind(BATCH_CHALLENGE, &data()?)?;
+    let challenge = transcript.compute_challenge(BATCH_CHALLENGE)?;
+    let r = Fr::from_bytes_be_mod_order(&challenge).map_err(Error::Field)?;
+
+    let mut coefficients = Vec::with_capacity(nb);
+    let mut ri = Fr::one();
+    for _ in 0..nb {
+        coefficients.push(ri);
+        ri *= r;
+    }
+
+    Ok(coefficients)
+}
@@ -0,0 +1,443 @@
+//! Wasm bindings verifying SP1 proofs against the embedded SP1 circuit verifying keys.
+
+use js_sys::{Array, Uint8Array};
+use lazy_static::lazy_static;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    converter::{fr_from_be_bytes, fr_from_str},
+    error::Error,
+    prepare_verifying_key, solidity_public_inputs, sp1_public_inputs, Fr, Groth16Error,
+    Groth16Proof, Groth16Verifier, Groth16VerifyingKey, KeyValidation, PlonkProof, PlonkVerifier,
+    PreparedVerifyingKey, SP1Calldata, SP1CircuitKey, SP1Error, SP1ProofBundle, SP1ProofKind,
+    SP1Verifier, VerificationOutcome,
+};
+
+type PreparedKeys = Vec<(
+    &'static SP1CircuitKey,
+    Result<PreparedVerifyingKey, Groth16Error>,
+)>;
+
+lazy_static! {
+    // Prepared once for every Groth16 proof checked against them
+    static ref GROTH16_PREPARED_VKS: PreparedKeys = SP1CircuitKey::all()
+        .iter()
+        .filter(|key| key.kind == SP1ProofKind::Groth16)
+        .map(|key| {
+            let vk =
+                Groth16VerifyingKey::from_bytes_with_validation(key.bytes, KeyValidation::Trusted);
+            (key, vk.map(prepare_verifying_key))
+        })
+        .collect();
+}
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub enum ProofMode {
+    Groth16,
+    Plonk,
+}
+
+impl From<ProofMode> for SP1ProofKind {
+    fn from(mode: ProofMode) -> Self {
+        match mode {
+            ProofMode::Groth16 => SP1ProofKind::Groth16,
+            ProofMode::Plonk => SP1ProofKind::Plonk,
+        }
+    }
+}
+
+#[wasm_bindgen]
+/// Result of a verification, as seen from JavaScript
+#[derive(Debug, Clone)]
+pub struct VerificationResult {
+    outcome: VerificationOutcome,
+    public_values: Option<Vec<u8>>,
+}
+
+#[wasm_bindgen]
+impl VerificationResult {
+    /// True only if the proof is valid
+    #[wasm_bindgen(getter)]
+    pub fn is_valid(&self) -> bool {
+        self.outcome.is_valid()
+    }
+
+    /// One of "valid", "invalid" or "malformed"
+    #[wasm_bindgen(getter)]
+    pub fn status(&self) -> String {
+        match self.outcome {
+            VerificationOutcome::Valid => "valid",
+            VerificationOutcome::Invalid { .. } => "invalid",
+            VerificationOutcome::Malformed { .. } => "malformed",
+        }
+        .to_string()
+    }
+
+    /// Why the proof is invalid or malformed
+    #[wasm_bindgen(getter)]
+    pub fn message(&self) -> Option<String> {
+        match &self.outcome {
+            VerificationOutcome::Valid => None,
+            VerificationOutcome::Invalid { reason } => Some(reason.clone()),
+            VerificationOutcome::Malformed { error } => Some(error.clone()),
+        }
+    }
+
+    /// The public values committed by the program, only set if the proof is valid
+    #[wasm_bindgen(getter)]
+    pub fn public_values(&self) -> Option<Vec<u8>> {
+        self.public_values.clone()
+    }
+}
+
+impl VerificationResult {
+    fn with_public_values(outcome: VerificationOutcome, public_values: Vec<u8>) -> Self {
+        let public_values = outcome.is_valid().then_some(public_values);
+        VerificationResult {
+            outcome,
+            public_values,
+        }
+    }
+}
+
+impl From<VerificationOutcome> for VerificationResult {
+    fn from(outcome: VerificationOutcome) -> Self {
+        VerificationResult {
+            outcome,
+            public_values: None,
+        }
+    }
+}
+
+impl From<VerificationResult> for VerificationOutcome {
+    fn from(result: VerificationResult) -> Self {
+        result.outcome
+    }
+}
+
+#[wasm_bindgen]
+/// WASM to verify an SP1 proof and read its public values
+pub fn verify_proof(contents: &[u8], method: ProofMode) -> VerificationResult {
+    verify_sp1_proof(contents, method)
+}
+
+#[wasm_bindgen]
+/// WASM to verify a batch of SP1 Groth16 proofs and read their public values
+pub fn verify_groth16_proofs(contents: Vec<Uint8Array>) -> Vec<VerificationResult> {
+    let contents: Vec<_> = contents.iter().map(Uint8Array::to_vec).collect();
+    let contents: Vec<_> = contents.iter().map(Vec::as_slice).collect();
+    verify_groth16_proof_batch(&contents)
+}
+
+/// Same as [`verify_groth16_proofs`], for proofs held in Rust
+pub fn verify_groth16_proof_batch(contents: &[&[u8]]) -> Vec<VerificationResult> {
+    let mut results = vec![VerificationOutcome::Valid.into(); contents.len()];
+
+    // Proofs are batched per circuit verifying key
+    let mut batches: Vec<(&'static SP1CircuitKey, Vec<(usize, SP1ProofBundle)>)> = Vec::new();
+    for (i, contents) in contents.iter().enumerate() {
+        match decode_sp1_proof(contents, ProofMode::Groth16) {
+            Ok((bundle, key)) => match batches.iter_mut().find(|(k, _)| k.version == key.version) {
+                Some((_, decoded)) => decoded.push((i, bundle)),
+                None => batches.push((key, vec![(i, bundle)])),
+            },
+            Err(outcome) => results[i] = outcome.into(),
+        }
+    }
+
+    for (key, decoded) in batches {
+        let pvk = match prepared_groth16_vk(key) {
+            Ok(pvk) => pvk,
+            Err(outcome) => {
+                for (i, _) in decoded {
+                    results[i] = outcome.clone().into();
+                }
+                continue;
+            }
+        };
+
+        let batch: Vec<_> = decoded
+            .iter()
+            .map(|(_, bundle)| (bundle.raw_proof.as_slice(), bundle.public_inputs.as_slice()))
+            .collect();
+        let outcomes = Groth16Verifier::verify_batch(&batch, pvk);
+        for ((i, bundle), outcome) in decoded.into_iter().zip(outcomes) {
+            results[i] = VerificationResult::with_public_values(outcome, bundle.public_values);
+        }
+    }
+
+    results
+}
+
+#[wasm_bindgen]
+/// WASM to verify a gnark-serialized SP1 proof of the program with the given `0x`-prefixed
+/// verifying key hash, committing the given public values, against the circuit verifying key of
+/// the most recent embedded SP1 version
+pub fn verify_sp1(
+    proof_bytes: &[u8],
+    sp1_public_values: &[u8],
+    program_vkey_hash: &str,
+    mode: ProofMode,
+) -> VerificationResult {
+    let public_inputs = match sp1_public_inputs(program_vkey_hash, sp1_public_values) {
+        Ok(public_inputs) => public_inputs,
+        Err(e) => return VerificationOutcome::malformed(e).into(),
+    };
+
+    let key = SP1CircuitKey::latest(mode.into());
+    let outcome = verify_with_key(proof_bytes, &public_inputs, key);
+
+    VerificationResult::with_public_values(outcome, sp1_public_values.to_vec())
+}
+
+#[wasm_bindgen]
+/// WASM to verify an SP1 proof, as `SP1ProofWithPublicValues::bytes` returns it, of the program
+/// with the given `0x`-prefixed verifying key hash committing the given public values, against the
+/// embedded circuit verifying key its verifier selector picks
+pub fn verify_sp1_onchain(
+    proof_bytes: &[u8],
+    sp1_public_values: &[u8],
+    program_vkey_hash: &str,
+    mode: ProofMode,
+) -> VerificationResult {
+    let outcome = SP1Verifier::verify_onchain(
+        proof_bytes,
+        sp1_public_values,
+        program_vkey_hash,
+        mode.into(),
+    );
+
+    VerificationResult::with_public_values(outcome, sp1_public_values.to_vec())
+}
+
+#[wasm_bindgen]
+/// WASM to verify an SP1 proof in the JSON format of `examples/binaries/*.bin.json`
+pub fn verify_json(json: &str, mode: ProofMode) -> VerificationResult {
+    SP1Verifier::verify_json(json, mode.into()).into()
+}
+
+#[wasm_bindgen]
+/// WASM to verify the proof in hex calldata of a `verifyProof(bytes32,bytes,bytes)` call to the SP1
+/// verifier gateway and read its public values
+pub fn verify_sp1_calldata(calldata: &str) -> VerificationResult {
+    let calldata = match SP1Calldata::from_hex(calldata) {
+        Ok(calldata) => calldata,
+        Err(e) => {
+            return VerificationOutcome::malformed(format!("Failed to read calldata: {}", e)).into()
+        }
+    };
+
+    let outcome = SP1Verifier::verify_calldata(&calldata);
+
+    VerificationResult::with_public_values(outcome, calldata.public_values)
+}
+
+#[wasm_bindgen]
+/// WASM to verify a gnark Groth16 proof against a gnark verifying key, with public inputs given as
+/// decimal strings, `0x`-prefixed hex strings or 32-byte big-endian arrays
+pub fn verify_groth16_proof(
+    proof: &[u8],
+    vk: &[u8],
+    public_inputs: Vec<JsValue>,
+) -> VerificationResult {
+    verify_with_public_inputs(parse_public_inputs(&public_inputs), |public_inputs| {
+        Groth16Verifier::verify(proof, vk, public_inputs)
+    })
+}
+
+/// Same as [`verify_groth16_proof`], for public inputs held in Rust as decimal or `0x`-prefixed
+/// hex strings
+pub fn verify_groth16_proof_with_inputs(
+    proof: &[u8],
+    vk: &[u8],
+    public_inputs: &[&str],
+) -> VerificationResult {
+    verify_with_public_inputs(parse_str_public_inputs(public_inputs), |public_inputs| {
+        Groth16Verifier::verify(proof, vk, public_inputs)
+    })
+}
+
+#[wasm_bindgen]
+/// WASM to verify a gnark PlonK proof against a gnark verifying key, with public inputs given as
+/// decimal strings, `0x`-prefixed hex strings or 32-byte big-endian arrays
+pub fn verify_plonk_proof(
+    proof: &[u8],
+    vk: &[u8],
+    public_inputs: Vec<JsValue>,
+) -> VerificationResult {
+    verify_with_public_inputs(parse_public_inputs(&public_inputs), |public_inputs| {
+        PlonkVerifier::verify(proof, vk, public_inputs)
+    })
+}
+
+/// Same as [`verify_plonk_proof`], for public inputs held in Rust as decimal or `0x`-prefixed hex
+/// strings
+pub fn verify_plonk_proof_with_inputs(
+    proof: &[u8],
+    vk: &[u8],
+    public_inputs: &[&str],
+) -> VerificationResult {
+    verify_with_public_inputs(parse_str_public_inputs(public_inputs), |public_inputs| {
+        PlonkVerifier::verify(proof, vk, public_inputs)
+    })
+}
+
+#[wasm_bindgen]
+/// WASM to encode the `verifyProof` call submitting an SP1 proof to the SP1 verifier gateway, as
+/// `0x`-prefixed hex transaction input
+pub fn sp1_gateway_calldata(contents: &[u8], method: ProofMode) -> Result<String, JsError> {
+    // Proofs with a wrong kind or tampered public values are not encoded
+    let (bundle, _) = decode_sp1_proof(contents, method).map_err(|outcome| {
+        let result = VerificationResult::from(outcome);
+        JsError::new(&result.message().unwrap_or_default())
+    })?;
+    Ok(SP1Calldata::from_bundle(&bundle).to_hex())
+}
+
+#[wasm_bindgen]
+/// WASM to encode a gnark Groth16 proof as the `uint256[8]` proof argument of the gnark Solidity
+/// verifier
+pub fn groth16_proof_to_solidity(proof: &[u8]) -> Result<Vec<u8>, JsError> {
+    Groth16Proof::from_bytes(proof)
+        .and_then(|proof| proof.to_solidity_bytes())
+        .map_err(|e| JsError::new(&format!("Invalid proof: {}", e)))
+}
+
+#[wasm_bindgen]
+/// WASM to encode a gnark PlonK proof as the `bytes` proof argument of the gnark Solidity verifier
+pub fn plonk_proof_to_solidity(proof: &[u8]) -> Result<Vec<u8>, JsError> {
+    PlonkProof::from_bytes(proof)
+        .and_then(|proof| proof.to_solidity_bytes())
+        .map_err(|e| JsError::new(&format!("Invalid proof: {}", e)))
+}
+
+#[wasm_bindgen]
+/// WASM to encode public inputs, given as for `verify_groth16_proof`, as the `uint256[]` public
+/// input argument of the gnark Solidity verifiers
+pub fn public_inputs_to_solidity(public_inputs: Vec<JsValue>) -> Result<Vec<u8>, JsError> {
+    parse_public_inputs(&public_inputs)
+        .map(|public_inputs| solidity_public_inputs(&public_inputs))
+        .map_err(|e| JsError::new(&e))
+}
+
+#[wasm_bindgen]
+/// WASM to verify a circom Groth16 proof from the `proof.json`, `verification_key.json` and
+/// `public.json` files written by snarkjs
+pub fn verify_snarkjs_groth16(proof: &str, vk: &str, public_inputs: &str) -> VerificationResult {
+    Groth16Verifier::verify_snarkjs(proof, vk, public_inputs).into()
+}
+
+fn parse_public_inputs(values: &[JsValue]) -> Result<Vec<Fr>, String> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let parsed = if let Some(s) = value.as_string() {
+                fr_from_str(&s)
+            } else if value.is_instance_of::<Uint8Array>() || Array::is_array(value) {
+                fr_from_be_bytes(&Uint8Array::new(value).to_vec())
+            } else {
+                Err(Error::InvalidData)
+            };
+            parsed.map_err(|e| invalid_public_input(i, e))
+        })
+        .collect()
+}
+
+fn parse_str_public_inputs(values: &[&str]) -> Result<Vec<Fr>, String> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, value)| fr_from_str(value).map_err(|e| invalid_public_input(i, e)))
+        .collect()
+}
+
+fn invalid_public_input(i: usize, e: Error) -> String {
+    format!("Invalid public input {}: {}", i, e)
+}
+
+fn verify_with_public_inputs(
+    public_inputs: Result<Vec<Fr>, String>,
+    verify: impl FnOnce(&[Fr]) -> VerificationOutcome,
+) -> VerificationResult {
+    match public_inputs {
+        Ok(public_inputs) => verify(&public_inputs).into(),
+        Err(e) => VerificationOutcome::malformed(e).into(),
+    }
+}
+
+fn verify_sp1_proof(contents: &[u8], method: ProofMode) -> VerificationResult {
+    let (bundle, key) = match decode_sp1_proof(contents, method) {
+        Ok(decoded) => decoded,
+        Err(outcome) => return outcome.into(),
+    };
+
+    let outcome = verify_with_key(&bundle.raw_proof, &bundle.public_inputs, key);
+
+    VerificationResult::with_public_values(outcome, bundle.public_values)
+}
+
+fn verify_with_key(proof: &[u8], public_inputs: &[Fr], key: &SP1CircuitKey) -> VerificationOutcome {
+    match key.kind {
+        SP1ProofKind::Groth16 => match prepared_groth16_vk(key) {
+            Ok(pvk) => Groth16Verifier::verify_prepared(proof, pvk, public_inputs),
+            Err(outcome) => outcome,
+        },
+        SP1ProofKind::Plonk => PlonkVerifier::verify_with_validation(
+            proof,
+            key.bytes,
+            public_inputs,
+            KeyValidation::Trusted,
+        ),
+    }
+}
+
+fn prepared_groth16_vk(
+    key: &SP1CircuitKey,
+) -> Result<&'static PreparedVerifyingKey, VerificationOutcome> {
+    let (_, pvk) = GROTH16_PREPARED_VKS
+        .iter()
+        .find(|(k, _)| k.version == key.version)
+        .ok_or_else(|| {
+            VerificationOutcome::malformed(format!(
+                "No prepared verification key for SP1 {}",
+                key.version
+            ))
+        })?;
+    pvk.as_ref()
+        .map_err(|e| VerificationOutcome::malformed(format!("Invalid verification key: {}", e)))
+}
+
+// Read a serialized SP1 proof, check its public values against the committed values digest and
+// find the circuit verifying key it was generated for
+fn decode_sp1_proof(
+    contents: &[u8],
+    method: ProofMode,
+) -> Result<(SP1ProofBundle, &'static SP1CircuitKey), VerificationOutcome> {
+    let expected = method.into();
+
+    let bundle = SP1ProofBundle::from_bytes(contents)
+        .and_then(|bundle| {
+            if bundle.kind == expected {
+                Ok(bundle)
+            } else {
+                Err(SP1Error::ProofKindMismatch {
+                    expected,
+                    got: bundle.kind,
+                })
+            }
+        })
+        .map_err(|e| VerificationOutcome::malformed(format!("Failed to read SP1 proof: {}", e)))?;
+
+    // The proof only attests to the digest, so tampered public values are an invalid claim
+    bundle
+        .check_public_values()
+        .map_err(VerificationOutcome::invalid)?;
+
+    let key = bundle
+        .circuit_key()
+        .map_err(|e| VerificationOutcome::malformed(format!("Failed to read SP1 proof: {}", e)))?;
+
+    Ok((bundle, key))
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::{bundle, PROGRAMS};
+use snark_bn254_verifier::{
+    prepare_verifying_key, Groth16Verifier, Groth16VerifyingKey, KeyValidation, PlonkVerifier,
+    PlonkVerifyingKey, PreparedVerifyingKey, SP1CircuitKey, SP1ProofKind, VerificationOutcome,
+};
+
+fn groth16_pvk() -> PreparedVerifyingKey {
+    let key = SP1CircuitKey::latest(SP1ProofKind::Groth16);
+    prepare_verifying_key(Groth16VerifyingKey::from_bytes(key.bytes).unwrap())
+}
+
+#[test]
+fn verifies_groth16_batches() {
+    let pvk = groth16_pvk();
+    let bundles: Vec<_> = PROGRAMS
+        .iter()
+        .map(|p| bundle(p, SP1ProofKind::Groth16))
+        .collect();
+    let proofs: Vec<_> = bundles
+        .iter()
+        .map(|b| (b.raw_proof.as_slice(), b.public_inputs.as_slice()))
+        .collect();
+
+    assert!(Groth16Verifier::verify_batch(&[], &pvk).is_empty());
+    assert_eq!(
+        Groth16Verifier::verify_batch(&proofs[..1], &pvk),
+        [VerificationOutcome::Valid]
+    );
+    assert_eq!(
+        Groth16Verifier::verify_batch(&proofs, &pvk),
+        vec![VerificationOutcome::Valid; 4]
+    );
+}
+
+#[test]
+fn reports_each_bad_proof_of_a_groth16_batch() {
+    let pvk = groth16_pvk();
+    let bundles: Vec<_> = PROGRAMS
+        .iter()
+        .map(|p| bundle(p, SP1ProofKind::Groth16))
+        .collect();
+
+    // Krs of the second proof replaced by Ar, a valid point that fails the pairing check
+    let mut corrupted = bundles[1].raw_proof.clone();
+    corrupted.copy_within(..64, 192);
+    let truncated = &bundles[3].raw_proof[..100];
+
+    let proofs = [
+        (
+            bundles[0].raw_proof.as_slice(),
+            bundles[0].public_inputs.as_slice(),
+        ),
+        (corrupted.as_slice(), bundles[1].public_inputs.as_slice()),
+        (
+            bundles[2].raw_proof.as_slice(),
+            bundles[2].public_inputs.as_slice(),
+        ),
+        (truncated, bundles[3].public_inputs.as_slice()),
+    ];
+    let outcomes = Groth16Verifier::verify_batch(&proofs, &pvk);
+    assert_eq!(outcomes[0], VerificationOutcome::Valid);
+    assert!(
+        matches!(outcomes[1], VerificationOutcome::Invalid { .. }),
+        "{:?}",
+        outcomes[1]
+    );
+    assert_eq!(outcomes[2], VerificationOutcome::Valid);
+    assert!(
+        matches!(outcomes[3], VerificationOutcome::Malformed { .. }),
+        "{:?}",
+        outcomes[3]
+    );
+
+    // A lone bad proof
+    let outcomes = Groth16Verifier::verify_batch(&proofs[1..2], &pvk);
+    assert!(
+        matches!(outcomes[..], [VerificationOutcome::Invalid { .. }]),
+        "{:?}",
+        outcomes
+    );
+}
+
+#[test]
+fn reports_each_bad_proof_of_a_plonk_batch() {
+    let key = SP1CircuitKey::latest(SP1ProofKind::Plonk);
+    let vk =
+        PlonkVerifyingKey::from_bytes_with_validation(key.bytes, KeyValidation::Trusted).unwrap();
+    let bundles: Vec<_> = PROGRAMS
+        .iter()
+        .map(|p| bundle(p, SP1ProofKind::Plonk))
+        .collect();
+
+    // The opening proof at ζ of the third proof replaced by L, a valid point that fails the final
+    // pairing check
+    let mut corrupted = bundles[2].raw_proof.clone();
+    corrupted.copy_within(..64, 448);
+    let truncated = &bundles[0].raw_proof[..100];
+
+    let proofs = [
+        (
+            bundles[0].raw_proof.as_slice(),
+            bundles[0].public_inputs.as_slice(),
+        ),
+        (truncated, bundles[0].public_inputs.as_slice()),
+        (
+            bundles[1].raw_proof.as_slice(),
+            bundles[1].public_inputs.as_slice(),
+        ),
+        (corrupted.as_slice(), bundles[2].public_inputs.as_slice()),
+        (
+            bundles[3].raw_proof.as_slice(),
+            bundles[3].public_inputs.as_slice(),
+        ),
+    ];
+    let outcomes = PlonkVerifier::verify_batch(&proofs, &vk);
+    assert_eq!(outcomes[0], VerificationOutcome::Valid);
+    assert!(
+        matches!(outcomes[1], VerificationOutcome::Malformed { .. }),
+        "{:?}",
+        outcomes[1]
+    );
+    assert_eq!(outcomes[2], VerificationOutcome::Valid);
+    assert!(
+        matches!(outcomes[3], VerificationOutcome::Invalid { .. }),
+        "{:?}",
+        outcomes[3]
+    );
+    assert_eq!(outcomes[4], VerificationOutcome::Valid);
+
+    let valid = [proofs[0], proofs[2], proofs[4]];
+    assert_eq!(
+        PlonkVerifier::verify_batch(&valid, &vk),
+        vec![VerificationOutcome::Valid; 3]
+    );
+}
@@ -0,0 +1,224 @@
+use bn::{AffineG1, AffineG2, Fr, Group, G1, G2};
+use sha2::{Digest, Sha256};
+use snark_bn254_verifier::{
+    kzg::KZGVerifyingKey, Groth16Proof, Groth16Verifier, Groth16VerifyingKey, PointEncoding,
+    VerificationOutcome,
+};
+
+// A Groth16 key and proof in gnark's layout for a circuit with one public input and one
+// `api.Commit` commitment to it, as gnark's `WriteRawTo` writes them. They are built from known
+// toxic waste rather than by gnark, so that every value of the commitment check is under control.
+struct Fixture {
+    vk: Vec<u8>,
+    proof: Vec<u8>,
+    public_inputs: Vec<Fr>,
+}
+
+// Trapdoor of the Pedersen key, and discrete logarithm of the commitment
+const SIGMA: u64 = 0x5ace;
+const COMMITTED: u64 = 0xcd66;
+
+fn fr(n: u64) -> Fr {
+    Fr::from_str(&n.to_string()).unwrap()
+}
+
+fn g1(scalar: Fr) -> AffineG1 {
+    AffineG1::from_jacobian(G1::one() * scalar).unwrap()
+}
+
+fn g2(scalar: Fr) -> AffineG2 {
+    AffineG2::from_jacobian(G2::one() * scalar).unwrap()
+}
+
+// Uncompressed points, through the uncompressed KZG verifying key [G₂, [α]G₂, G₁]
+fn g1_bytes(point: AffineG1) -> Vec<u8> {
+    let generator = g2(Fr::one());
+    let vk = KZGVerifyingKey::new(point, generator, generator);
+    vk.to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap()[256..320]
+        .to_vec()
+}
+
+fn g2_bytes(point: AffineG2) -> Vec<u8> {
+    let vk = KZGVerifyingKey::new(g1(Fr::one()), point, point);
+    vk.to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap()[..128]
+        .to_vec()
+}
+
+// hash_to_field(commitment || committed public inputs) with expand_message_xmd over SHA-256 from
+// RFC 9380, as gnark derives the public input bound to a commitment
+fn commitment_hash(commitment: AffineG1, committed: &[[u8; 32]]) -> Fr {
+    let dst = b"bsb22-commitment";
+    let dst_prime = [&dst[..], &[dst.len() as u8]].concat();
+    let mut message = g1_bytes(commitment);
+    for input in committed {
+        message.extend_from_slice(input);
+    }
+
+    let b0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(&message)
+        .chain_update([0, 48, 0])
+        .chain_update(&dst_prime)
+        .finalize();
+    let b1 = Sha256::new()
+        .chain_update(b0)
+        .chain_update([1])
+        .chain_update(&dst_prime)
+        .finalize();
+    let xor: Vec<u8> = b0.iter().zip(b1.iter()).map(|(x, y)| x ^ y).collect();
+    let b2 = Sha256::new()
+        .chain_update(xor)
+        .chain_update([2])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    Fr::from_bytes_be_mod_order(&[&b1[..], &b2[..16]].concat()).unwrap()
+}
+
+fn fixture() -> Fixture {
+    let (alpha, beta, gamma, delta) = (fr(0x1357), fr(0x2468), fr(0x369c), fr(0x48d1));
+    let (sigma, pedersen_g) = (fr(SIGMA), fr(0x6b0d));
+    let k = [fr(0x7e11), fr(0x8f22), fr(0x9a33)];
+    let (a, b, c) = (fr(0xab44), fr(0xbc55), fr(COMMITTED));
+    let input = 7u8;
+
+    let mut committed_input = [0u8; 32];
+    committed_input[31] = input;
+    let commitment = g1(c);
+    let hash = commitment_hash(commitment, &[committed_input]);
+
+    // e(A, B) * e(L, -[γ]₂) * e(C, -[δ]₂) == e(α, β) with L = Σxᵢ.Kᵢ + commitment
+    let l = k[0] + fr(input.into()) * k[1] + hash * k[2] + c;
+    let krs = (a * b - alpha * beta - l * gamma) * delta.inverse().unwrap();
+
+    let mut vk = [
+        g1_bytes(g1(alpha)),
+        g1_bytes(g1(beta)),
+        g2_bytes(g2(beta)),
+        g2_bytes(g2(gamma)),
+        g1_bytes(g1(delta)),
+        g2_bytes(g2(delta)),
+    ]
+    .concat();
+    vk.extend_from_slice(&(k.len() as u32).to_be_bytes());
+    for k in k {
+        vk.extend_from_slice(&g1_bytes(g1(k)));
+    }
+    // One commitment to the public input, wire 1
+    vk.extend_from_slice(&1u32.to_be_bytes());
+    vk.extend_from_slice(&1u32.to_be_bytes());
+    vk.extend_from_slice(&1u64.to_be_bytes());
+    // Pedersen key: G and -σG, which the proof of knowledge σ.commitment satisfies
+    vk.extend_from_slice(&g2_bytes(g2(pedersen_g)));
+    vk.extend_from_slice(&g2_bytes(g2(-(pedersen_g * sigma))));
+
+    let mut proof = [g1_bytes(g1(a)), g2_bytes(g2(b)), g1_bytes(g1(krs))].concat();
+    proof.extend_from_slice(&1u32.to_be_bytes());
+    proof.extend_from_slice(&g1_bytes(commitment));
+    proof.extend_from_slice(&g1_bytes(g1(c * sigma)));
+
+    Fixture {
+        vk,
+        proof,
+        public_inputs: vec![fr(input.into())],
+    }
+}
+
+// Offsets of the commitment and its proof of knowledge in the uncompressed proof
+const COMMITMENT: usize = 260;
+const COMMITMENT_POK: usize = 324;
+
+fn replace_g1(bytes: &mut [u8], offset: usize, point: AffineG1) {
+    bytes[offset..offset + 64].copy_from_slice(&g1_bytes(point));
+}
+
+fn assert_invalid(outcome: VerificationOutcome) {
+    assert!(
+        matches!(outcome, VerificationOutcome::Invalid { .. }),
+        "{:?}",
+        outcome
+    );
+}
+
+#[test]
+fn verifies_proofs_with_commitments() {
+    let fixture = fixture();
+    assert_eq!(
+        Groth16Verifier::verify(&fixture.proof, &fixture.vk, &fixture.public_inputs),
+        VerificationOutcome::Valid
+    );
+
+    // Same key and proof with compressed points, as written by gnark's `WriteTo`
+    let vk = Groth16VerifyingKey::from_bytes(&fixture.vk).unwrap();
+    let proof = Groth16Proof::from_bytes(&fixture.proof).unwrap();
+    assert_eq!(proof.to_bytes().unwrap(), fixture.proof);
+    assert_eq!(
+        Groth16Verifier::verify(
+            &proof
+                .to_bytes_with_encoding(PointEncoding::Compressed)
+                .unwrap(),
+            &vk.to_bytes().unwrap(),
+            &fixture.public_inputs
+        ),
+        VerificationOutcome::Valid
+    );
+
+    assert_invalid(Groth16Verifier::verify(
+        &fixture.proof,
+        &fixture.vk,
+        &[fr(8)],
+    ));
+}
+
+#[test]
+fn rejects_tampered_commitments() {
+    let fixture = fixture();
+    // Another commitment, whose proof of knowledge no longer holds
+    let mut proof = fixture.proof.clone();
+    replace_g1(&mut proof, COMMITMENT, g1(fr(COMMITTED + 1)));
+    assert_invalid(Groth16Verifier::verify(
+        &proof,
+        &fixture.vk,
+        &fixture.public_inputs,
+    ));
+
+    // Another commitment with a matching proof of knowledge, which changes the public input it is
+    // hashed to
+    replace_g1(
+        &mut proof,
+        COMMITMENT_POK,
+        g1(fr(COMMITTED + 1) * fr(SIGMA)),
+    );
+    assert_invalid(Groth16Verifier::verify(
+        &proof,
+        &fixture.vk,
+        &fixture.public_inputs,
+    ));
+}
+
+#[test]
+fn rejects_tampered_commitment_proofs_of_knowledge() {
+    let fixture = fixture();
+
+    let mut proof = fixture.proof.clone();
+    replace_g1(
+        &mut proof,
+        COMMITMENT_POK,
+        g1(fr(COMMITTED) * fr(SIGMA + 1)),
+    );
+    assert_invalid(Groth16Verifier::verify(
+        &proof,
+        &fixture.vk,
+        &fixture.public_inputs,
+    ));
+
+    // The point at infinity, which gnark writes when there are no commitments
+    proof[COMMITMENT_POK..].fill(0);
+    assert_invalid(Groth16Verifier::verify(
+        &proof,
+        &fixture.vk,
+        &fixture.public_inputs,
+    ));
+}
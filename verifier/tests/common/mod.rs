@@ -0,0 +1,77 @@
+// Fixtures shared by the integration tests, which each use a part of them
+#![allow(dead_code)]
+
+use std::{fs, path::PathBuf};
+
+use snark_bn254_verifier::{SP1CircuitKey, SP1ProofBundle, SP1ProofKind};
+
+pub const PROGRAMS: [&str; 4] = ["fibonacci", "is-prime", "sha2", "tendermint"];
+
+// Program verifying key hashes, as given by `SP1VerifyingKey::bytes32`
+pub const PROGRAM_VKEY_HASHES: [(&str, &str); 4] = [
+    (
+        "fibonacci",
+        "0x00bfb1b34ca3a3d9689541f84382285ce06998a45ab411edfa54f24118222e1e",
+    ),
+    (
+        "is-prime",
+        "0x002d439c7212711e5a1de3e8a139921281e4e770b11dfeaae2735090098a11bd",
+    ),
+    (
+        "sha2",
+        "0x00bce5c96c06fe6c875aaa4d46af11bc8e39debe14b0cc789dbc232e910dcb28",
+    ),
+    (
+        "tendermint",
+        "0x0057c39436b67829f76ad36031a3b730fd911633663f7f1ccdde75f1dbc6e6b4",
+    ),
+];
+
+// Reads a file from its path relative to the root of the repository
+pub fn read(path: &str) -> Vec<u8> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join(path);
+    fs::read(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path.display(), e))
+}
+
+// Name of the kind in the fixture paths
+pub fn kind_name(kind: SP1ProofKind) -> &'static str {
+    match kind {
+        SP1ProofKind::Groth16 => "groth16",
+        SP1ProofKind::Plonk => "plonk",
+    }
+}
+
+// An example proof as saved by `SP1ProofWithPublicValues::save`
+pub fn bundle_bytes(program: &str, kind: SP1ProofKind) -> Vec<u8> {
+    read(&format!(
+        "examples/binaries/{}_{}_proof.bin",
+        program,
+        kind_name(kind)
+    ))
+}
+
+pub fn bundle(program: &str, kind: SP1ProofKind) -> SP1ProofBundle {
+    SP1ProofBundle::from_bytes(&bundle_bytes(program, kind)).unwrap()
+}
+
+// The same proof in the JSON format written by the example script
+pub fn json(program: &str, kind: SP1ProofKind) -> String {
+    let path = format!(
+        "examples/binaries/{}_{}_proof.bin.json",
+        program,
+        kind_name(kind)
+    );
+    String::from_utf8(read(&path)).unwrap()
+}
+
+// The compressed verifying key of the latest SP1 circuit
+pub fn key(kind: SP1ProofKind) -> Vec<u8> {
+    SP1CircuitKey::latest(kind).bytes.to_vec()
+}
+
+// A file written by snarkjs for a circuit with two public signals
+pub fn read_snarkjs(name: &str) -> String {
+    String::from_utf8(read(&format!("examples/snarkjs/{}.json", name))).unwrap()
+}
@@ -0,0 +1,522 @@
+mod common;
+
+use bn::{AffineG1, AffineG2, Fq, Fr, Group, G1, G2};
+use common::{bundle, read_snarkjs, PROGRAMS};
+use snark_bn254_verifier::{
+    kzg::KZGVerifyingKey, solidity_public_inputs, Error, Groth16Error, Groth16Proof,
+    Groth16Verifier, Groth16VerifyingKey, KeyValidation, PlonkError, PlonkProof, PlonkVerifier,
+    PlonkVerifyingKey, PointEncoding, SP1CircuitKey, SP1ProofKind, VerificationOutcome,
+};
+
+const SCALAR_MODULUS: &str = "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001";
+
+// Adds a modulus to a 32-byte big-endian value, giving another encoding of the same element
+fn add_modulus(bytes: &mut [u8], modulus: [u8; 32]) {
+    let mut carry = 0u16;
+    for (byte, m) in bytes.iter_mut().zip(modulus).rev() {
+        let sum = u16::from(*byte) + u16::from(m) + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+    }
+    assert_eq!(carry, 0);
+}
+
+#[test]
+fn writes_sp1_circuit_keys_back() {
+    for key in SP1CircuitKey::all() {
+        let written = match key.kind {
+            SP1ProofKind::Groth16 => Groth16VerifyingKey::from_bytes(key.bytes)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            SP1ProofKind::Plonk => PlonkVerifyingKey::from_bytes(key.bytes)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+        };
+        assert!(written == key.bytes, "{} {:?}", key.version, key.kind);
+    }
+}
+
+// gnark-crypto's `kzg.VerifyingKey.WriteTo` puts G₂ and [α]G₂ before G₁, unlike the key embedded in
+// PlonK keys, followed by the precomputed pairing lines
+#[test]
+fn reads_and_writes_gnark_crypto_kzg_keys() {
+    let plonk_key = SP1CircuitKey::latest(SP1ProofKind::Plonk).bytes;
+    let num_qcp = u32::from_be_bytes(plonk_key[368..372].try_into().unwrap()) as usize;
+    let start = 372 + 32 * num_qcp;
+    let (g1, g2_points) = (
+        &plonk_key[start..start + 32],
+        &plonk_key[start + 32..start + 160],
+    );
+    // The lines are skipped and written back as zeros
+    let mut gnark_crypto_key = [g2_points, g1].concat();
+    gnark_crypto_key.resize(160 + 4 * 66 * 128, 1);
+
+    let written = KZGVerifyingKey::from_bytes(&gnark_crypto_key)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    assert_eq!(written.len(), gnark_crypto_key.len());
+    assert_eq!(written[..160], gnark_crypto_key[..160]);
+    assert!(written[160..].iter().all(|byte| *byte == 0));
+    let points = KZGVerifyingKey::from_bytes(&gnark_crypto_key[..160]).unwrap();
+    assert!(points.to_bytes().unwrap() == written);
+
+    // Truncated lines
+    assert!(KZGVerifyingKey::from_bytes(&gnark_crypto_key[..gnark_crypto_key.len() - 1]).is_err());
+}
+
+#[test]
+fn writes_sp1_proofs_back() {
+    for program in PROGRAMS {
+        let groth16 = bundle(program, SP1ProofKind::Groth16);
+        let proof = Groth16Proof::from_bytes(&groth16.raw_proof).unwrap();
+        assert_eq!(proof.to_bytes().unwrap(), groth16.raw_proof, "{}", program);
+
+        let plonk = bundle(program, SP1ProofKind::Plonk);
+        let proof = PlonkProof::from_bytes(&plonk.raw_proof).unwrap();
+        assert_eq!(proof.to_bytes().unwrap(), plonk.raw_proof, "{}", program);
+    }
+}
+
+#[test]
+fn written_proofs_still_verify() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Plonk);
+    let vk = PlonkVerifyingKey::from_bytes(SP1CircuitKey::latest(SP1ProofKind::Plonk).bytes)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    let proof = PlonkProof::from_bytes(&bundle.raw_proof)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    assert_eq!(
+        PlonkVerifier::verify(&proof, &vk, &bundle.public_inputs),
+        VerificationOutcome::Valid
+    );
+}
+
+// gnark always writes the commitment section, so Ar, Bs and Krs alone are a truncated proof
+#[test]
+fn rejects_proofs_without_commitment_section() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    assert!(matches!(
+        Groth16Proof::from_bytes(&bundle.raw_proof[..256]),
+        Err(Groth16Error::GeneralError(
+            Error::InsufficientBufferLength {
+                field: "number of commitments",
+                offset: 256,
+                ..
+            }
+        ))
+    ));
+
+    let mut trailing = bundle.raw_proof.clone();
+    trailing.extend_from_slice(&[1, 2, 3]);
+    assert!(matches!(
+        Groth16Proof::from_bytes(&trailing),
+        Err(Groth16Error::GeneralError(Error::TrailingBytes {
+            field: "Groth16 proof",
+            ..
+        }))
+    ));
+    assert!(matches!(
+        Groth16Verifier::verify(
+            &trailing,
+            SP1CircuitKey::latest(SP1ProofKind::Groth16).bytes,
+            &bundle.public_inputs
+        ),
+        VerificationOutcome::Malformed { .. }
+    ));
+
+    // Onchain proofs have their own encoding, read by `from_solidity_bytes`
+    let solidity = Groth16Proof::from_solidity_bytes(&bundle.encoded_proof).unwrap();
+    assert_eq!(solidity.to_bytes().unwrap(), bundle.raw_proof);
+}
+
+// gnark writes the point at infinity of G₂ with the infinity flag, which is the identity
+#[test]
+fn reads_compressed_g2_infinity_as_identity() {
+    let key = SP1CircuitKey::latest(SP1ProofKind::Groth16).bytes;
+    let vk = Groth16VerifyingKey::from_bytes(key).unwrap();
+    let mut raw_key = vk
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    // [γ]₂ follows [α]₁, [β]₁ and [β]₂
+    raw_key[256..384].fill(0);
+    let trusted =
+        Groth16VerifyingKey::from_bytes_with_validation(&raw_key, KeyValidation::Trusted).unwrap();
+    let compressed = trusted.to_bytes().unwrap();
+    assert_eq!(compressed[128], 0b01 << 6);
+    assert!(compressed[129..192].iter().all(|b| *b == 0));
+    assert_eq!(
+        Groth16VerifyingKey::from_bytes_with_validation(&compressed, KeyValidation::Trusted)
+            .unwrap()
+            .to_bytes_with_encoding(PointEncoding::Uncompressed)
+            .unwrap(),
+        raw_key
+    );
+    assert!(matches!(
+        Groth16VerifyingKey::from_bytes(&compressed),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "[γ]₂",
+            offset: 128,
+            source,
+        })) if matches!(*source, Error::UnexpectedIdentity)
+    ));
+}
+
+#[test]
+fn writes_snarkjs_keys_in_gnark_format() {
+    let vk = Groth16VerifyingKey::from_snarkjs_json(&read_snarkjs("verification_key")).unwrap();
+    let written = vk.to_bytes().unwrap();
+    assert_eq!(Groth16VerifyingKey::from_bytes(&written).unwrap(), vk);
+
+    let proof = Groth16Proof::from_snarkjs_json(&read_snarkjs("proof")).unwrap();
+    let public_inputs = [Fr::from_str("33").unwrap(), Fr::from_str("3").unwrap()];
+    assert_eq!(
+        Groth16Verifier::verify(&proof.to_bytes().unwrap(), &written, &public_inputs),
+        VerificationOutcome::Valid
+    );
+}
+
+// Each x has two points, flagged differently, so both flags of both encodings are exercised
+#[test]
+fn compressed_points_round_trip() {
+    for i in 1..64u64 {
+        let scalar = Fr::from_str(&(i * i * 7919 + 1).to_string()).unwrap();
+        let g1 = AffineG1::from_jacobian(G1::one() * scalar).unwrap();
+        let g2 = AffineG2::from_jacobian(G2::one() * scalar).unwrap();
+
+        for (g1, g2) in [(g1, g2), (-g1, -g2)] {
+            let vk = KZGVerifyingKey::new(g1, g2, -g2);
+            let written = vk.to_bytes().unwrap();
+            let read = KZGVerifyingKey::from_bytes(&written).unwrap();
+            assert_eq!(read.to_bytes().unwrap(), written, "{}", i);
+
+            let raw = vk
+                .to_bytes_with_encoding(PointEncoding::Uncompressed)
+                .unwrap();
+            let read = KZGVerifyingKey::from_bytes(&raw).unwrap();
+            assert_eq!(read.to_bytes().unwrap(), written, "{}", i);
+        }
+
+        let (positive, negative) = (
+            KZGVerifyingKey::new(g1, g2, g2).to_bytes().unwrap(),
+            KZGVerifyingKey::new(-g1, -g2, -g2).to_bytes().unwrap(),
+        );
+        assert_eq!(positive[0] & 0x3f, negative[0] & 0x3f);
+        assert_ne!(positive[0] & 0xc0, negative[0] & 0xc0);
+        assert_ne!(positive[128] & 0xc0, negative[128] & 0xc0);
+    }
+}
+
+// gnark's `WriteTo` compresses proofs, SP1 saves them with `WriteRawTo`
+#[test]
+fn verifies_compressed_proofs() {
+    let groth16_vk = SP1CircuitKey::latest(SP1ProofKind::Groth16).bytes;
+    let plonk_vk = SP1CircuitKey::latest(SP1ProofKind::Plonk).bytes;
+    for program in PROGRAMS {
+        let groth16 = bundle(program, SP1ProofKind::Groth16);
+        let proof = Groth16Proof::from_bytes(&groth16.raw_proof).unwrap();
+        let compressed = proof
+            .to_bytes_with_encoding(PointEncoding::Compressed)
+            .unwrap();
+        assert_eq!(compressed.len(), 32 + 64 + 32 + 4 + 32);
+        assert_eq!(
+            Groth16Proof::from_bytes(&compressed)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            groth16.raw_proof,
+            "{}",
+            program
+        );
+        assert_eq!(
+            Groth16Verifier::verify(&compressed, groth16_vk, &groth16.public_inputs),
+            VerificationOutcome::Valid,
+            "{}",
+            program
+        );
+
+        let plonk = bundle(program, SP1ProofKind::Plonk);
+        let proof = PlonkProof::from_bytes(&plonk.raw_proof).unwrap();
+        let compressed = proof
+            .to_bytes_with_encoding(PointEncoding::Compressed)
+            .unwrap();
+        assert_eq!(
+            PlonkProof::from_bytes(&compressed)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            plonk.raw_proof,
+            "{}",
+            program
+        );
+        assert_eq!(
+            PlonkVerifier::verify(&compressed, plonk_vk, &plonk.public_inputs),
+            VerificationOutcome::Valid,
+            "{}",
+            program
+        );
+    }
+}
+
+#[test]
+fn verifies_against_uncompressed_keys() {
+    let groth16 = bundle("fibonacci", SP1ProofKind::Groth16);
+    let key = SP1CircuitKey::latest(SP1ProofKind::Groth16).bytes;
+    let raw_key = Groth16VerifyingKey::from_bytes(key)
+        .unwrap()
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    assert_eq!(
+        Groth16VerifyingKey::from_bytes(&raw_key)
+            .unwrap()
+            .to_bytes()
+            .unwrap(),
+        key
+    );
+    assert_eq!(
+        Groth16Verifier::verify(&groth16.raw_proof, &raw_key, &groth16.public_inputs),
+        VerificationOutcome::Valid
+    );
+
+    let plonk = bundle("fibonacci", SP1ProofKind::Plonk);
+    let key = SP1CircuitKey::latest(SP1ProofKind::Plonk).bytes;
+    let raw_key = PlonkVerifyingKey::from_bytes(key)
+        .unwrap()
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    assert_eq!(
+        PlonkVerifyingKey::from_bytes(&raw_key)
+            .unwrap()
+            .to_bytes()
+            .unwrap(),
+        key
+    );
+    assert_eq!(
+        PlonkVerifier::verify(&plonk.raw_proof, &raw_key, &plonk.public_inputs),
+        VerificationOutcome::Valid
+    );
+}
+
+#[test]
+fn rejects_mixed_point_encodings() {
+    // Flag bits on Krs of an uncompressed proof
+    let mut groth16 = bundle("fibonacci", SP1ProofKind::Groth16).raw_proof;
+    groth16[192] |= 0b10 << 6;
+    assert!(matches!(
+        Groth16Proof::from_bytes(&groth16),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "Krs",
+            offset: 192,
+            source,
+        })) if matches!(*source, Error::InconsistentPointEncoding {
+            expected: PointEncoding::Uncompressed
+        })
+    ));
+
+    // An uncompressed [γ]₂ in a compressed key
+    let key = Groth16VerifyingKey::from_bytes(SP1CircuitKey::latest(SP1ProofKind::Groth16).bytes)
+        .unwrap();
+    let raw_key = key
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    let mut mixed = key.to_bytes().unwrap();
+    mixed.splice(128..192, raw_key[256..384].iter().copied());
+    assert!(matches!(
+        Groth16VerifyingKey::from_bytes(&mixed),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "[γ]₂",
+            offset: 128,
+            source,
+        })) if matches!(*source, Error::InconsistentPointEncoding {
+            expected: PointEncoding::Compressed
+        })
+    ));
+
+    // The shifted opening of Z, after the claimed values, without flag bits in a compressed proof
+    let plonk =
+        PlonkProof::from_bytes(&bundle("fibonacci", SP1ProofKind::Plonk).raw_proof).unwrap();
+    let mut compressed = plonk
+        .to_bytes_with_encoding(PointEncoding::Compressed)
+        .unwrap();
+    let num_claimed_values = u32::from_be_bytes(compressed[256..260].try_into().unwrap());
+    let z_shifted_opening = 260 + 32 * num_claimed_values as usize;
+    compressed[z_shifted_opening] &= 0x3f;
+    assert!(matches!(
+        PlonkProof::from_bytes(&compressed),
+        Err(PlonkError::GeneralError(Error::InvalidFieldEncoding {
+            field: "shifted opening proof of Z",
+            offset,
+            source,
+        })) if matches!(*source, Error::InconsistentPointEncoding {
+            expected: PointEncoding::Compressed
+        }) && offset == z_shifted_opening
+    ));
+}
+
+#[test]
+fn rejects_non_canonical_coordinates() {
+    let fq_modulus = Fq::modulus().to_bytes_be();
+
+    // x = 1 + p still leaves the flag bits of the compressed generator free
+    let g2 = AffineG2::from_jacobian(G2::one()).unwrap();
+    let vk = KZGVerifyingKey::new(AffineG1::from_jacobian(G1::one()).unwrap(), g2, -g2);
+    let mut compressed = vk.to_bytes().unwrap();
+    add_modulus(&mut compressed[128..160], fq_modulus);
+    assert_eq!(compressed[128] & 0xc0, 0b10 << 6);
+    assert!(matches!(
+        KZGVerifyingKey::from_bytes(&compressed),
+        Err(Error::InvalidFieldEncoding {
+            field: "KZG G₁",
+            offset: 128,
+            source,
+        }) if matches!(*source, Error::BeyondTheModulus)
+    ));
+
+    // y of Ar
+    let mut groth16 = bundle("fibonacci", SP1ProofKind::Groth16).raw_proof;
+    add_modulus(&mut groth16[32..64], fq_modulus);
+    assert!(matches!(
+        Groth16Proof::from_bytes(&groth16),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "Ar",
+            offset: 0,
+            source,
+        })) if matches!(*source, Error::BeyondTheModulus)
+    ));
+
+    // First claimed value, after the 8 uncompressed points and the number of claimed values
+    let mut plonk = bundle("fibonacci", SP1ProofKind::Plonk).raw_proof;
+    let mut scalar_modulus = [0u8; 32];
+    hex::decode_to_slice(SCALAR_MODULUS, &mut scalar_modulus).unwrap();
+    add_modulus(&mut plonk[516..548], scalar_modulus);
+    assert!(matches!(
+        PlonkProof::from_bytes(&plonk),
+        Err(PlonkError::GeneralError(Error::InvalidFieldEncoding {
+            field: "claimed value",
+            offset: 516,
+            source,
+        })) if matches!(*source, Error::BeyondTheModulus)
+    ));
+}
+
+#[test]
+fn rejects_points_at_infinity_with_stray_bits() {
+    // Proof of knowledge of a compressed proof without commitments
+    let proof =
+        Groth16Proof::from_bytes(&bundle("fibonacci", SP1ProofKind::Groth16).raw_proof).unwrap();
+    let mut compressed = proof
+        .to_bytes_with_encoding(PointEncoding::Compressed)
+        .unwrap();
+    assert_eq!(compressed[132], 0b01 << 6);
+    compressed[163] = 1;
+    assert!(matches!(
+        Groth16Proof::from_bytes(&compressed),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "commitment proof of knowledge",
+            offset: 132,
+            source,
+        })) if matches!(*source, Error::InvalidPoint)
+    ));
+
+    // x coordinate of G₂ in a KZG key
+    let g2 = AffineG2::from_jacobian(G2::one()).unwrap();
+    let mut vk = KZGVerifyingKey::new(AffineG1::from_jacobian(G1::one()).unwrap(), g2, -g2)
+        .to_bytes()
+        .unwrap();
+    vk[..64].fill(0);
+    vk[0] = 0b01 << 6;
+    vk[63] = 1;
+    assert!(matches!(
+        KZGVerifyingKey::from_bytes(&vk),
+        Err(Error::InvalidFieldEncoding {
+            field: "KZG G₂",
+            offset: 0,
+            source,
+        }) if matches!(*source, Error::InvalidPoint)
+    ));
+}
+
+// SP1 bundles carry the proof encoded for the Solidity verifier next to the gnark one
+#[test]
+fn encodes_groth16_proofs_like_sp1() {
+    for program in PROGRAMS {
+        let bundle = bundle(program, SP1ProofKind::Groth16);
+        let proof = Groth16Proof::from_bytes(&bundle.raw_proof).unwrap();
+        assert_eq!(
+            proof.to_solidity_bytes().unwrap(),
+            bundle.encoded_proof,
+            "{}",
+            program
+        );
+    }
+}
+
+#[test]
+fn encodes_plonk_proofs_like_sp1() {
+    let vk =
+        PlonkVerifyingKey::from_bytes(SP1CircuitKey::latest(SP1ProofKind::Plonk).bytes).unwrap();
+    for program in PROGRAMS {
+        let bundle = bundle(program, SP1ProofKind::Plonk);
+        let proof = PlonkProof::from_bytes(&bundle.raw_proof).unwrap();
+        assert_eq!(
+            proof.to_solidity_bytes().unwrap(),
+            bundle.encoded_proof,
+            "{}",
+            program
+        );
+
+        let decoded =
+            PlonkProof::from_solidity_bytes(&bundle.encoded_proof, &vk, &bundle.public_inputs)
+                .unwrap();
+        assert_eq!(
+            decoded.to_solidity_bytes().unwrap(),
+            bundle.encoded_proof,
+            "{}",
+            program
+        );
+    }
+}
+
+// The Solidity verifier gnark exports takes at most one commitment and its proof of knowledge
+#[test]
+fn encodes_groth16_proofs_with_one_commitment() {
+    let raw_proof = bundle("fibonacci", SP1ProofKind::Groth16).raw_proof;
+    let (points, ar) = (&raw_proof[..256], &raw_proof[..64]);
+    let with_commitments = |count: u32| {
+        let mut proof = [points, &count.to_be_bytes()].concat();
+        for _ in 0..=count {
+            proof.extend_from_slice(ar);
+        }
+        Groth16Proof::from_bytes(&proof).unwrap()
+    };
+
+    let encoded = with_commitments(1).to_solidity_bytes().unwrap();
+    assert_eq!(encoded.len(), 384);
+    let decoded = Groth16Proof::from_solidity_bytes(&encoded).unwrap();
+    assert_eq!(decoded.to_solidity_bytes().unwrap(), encoded);
+
+    assert!(matches!(
+        with_commitments(2).to_solidity_bytes(),
+        Err(Groth16Error::InvalidNumberOfCommitments)
+    ));
+}
+
+#[test]
+fn encodes_public_inputs_as_words() {
+    let encoded = solidity_public_inputs(&[Fr::from_str("1").unwrap(), -Fr::one()]);
+    assert_eq!(encoded.len(), 64);
+    assert_eq!(encoded[..32], {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        one
+    });
+    assert_eq!(
+        hex::encode(&encoded[32..]),
+        "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000000"
+    );
+}
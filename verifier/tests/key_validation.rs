@@ -0,0 +1,272 @@
+mod common;
+
+use bn::{AffineG2, Fq, Fq2};
+use common::{bundle, key};
+use snark_bn254_verifier::{
+    kzg::KZGVerifyingKey, Error, Groth16Error, Groth16Verifier, Groth16VerifyingKey, KeyValidation,
+    PlonkError, PlonkVerifier, PlonkVerifyingKey, PointEncoding, SP1CircuitKey, SP1ProofKind,
+    VerificationOutcome,
+};
+
+// Offsets of the compressed points of the SP1 Groth16 key, which has 3 K points and no commitments
+const G1_ALPHA: usize = 0;
+const G2_GAMMA: usize = 128;
+const G2_DELTA: usize = 224;
+const K: usize = 292;
+const COMMITMENT_KEY: usize = 392;
+
+// Offsets of S1 and Qm in a compressed PlonK key, after the domain, then S1, S2, S3, Ql and Qr
+const S1: usize = 112;
+const QM: usize = S1 + 5 * 32;
+
+// Offset of [α]G₂ in the KZG verifying key following the Qcp points of a compressed PlonK key
+fn plonk_alpha_g2(key: &[u8]) -> usize {
+    let num_qcp = u32::from_be_bytes(key[368..372].try_into().unwrap()) as usize;
+    372 + 32 * num_qcp + 32 + 64
+}
+
+// A compressed point of the twist curve outside of the prime-order subgroup, which holds a tiny
+// fraction of the curve points
+fn compressed_g2_outside_subgroup() -> [u8; 64] {
+    let x0 = (1u64..)
+        .find(|i| {
+            let x = Fq2::new(Fq::from_str(&i.to_string()).unwrap(), Fq::zero());
+            AffineG2::get_ys_from_x_unchecked(x).is_some()
+        })
+        .unwrap();
+
+    let mut point = [0u8; 64];
+    point[0] = 0b10 << 6;
+    point[56..].copy_from_slice(&x0.to_be_bytes());
+    point
+}
+
+fn compressed_infinity() -> [u8; 64] {
+    let mut point = [0u8; 64];
+    point[0] = 0b01 << 6;
+    point
+}
+
+#[test]
+fn embedded_keys_are_valid() {
+    for key in SP1CircuitKey::all() {
+        match key.kind {
+            SP1ProofKind::Groth16 => assert_eq!(
+                Groth16VerifyingKey::from_bytes(key.bytes).unwrap(),
+                Groth16VerifyingKey::from_bytes_with_validation(key.bytes, KeyValidation::Trusted)
+                    .unwrap()
+            ),
+            SP1ProofKind::Plonk => assert!(PlonkVerifyingKey::from_bytes(key.bytes).is_ok()),
+        }
+    }
+}
+
+#[test]
+fn rejects_g2_points_outside_subgroup() {
+    let mut groth16_key = key(SP1ProofKind::Groth16);
+    groth16_key[G2_GAMMA..G2_GAMMA + 64].copy_from_slice(&compressed_g2_outside_subgroup());
+    assert!(matches!(
+        Groth16VerifyingKey::from_bytes(&groth16_key),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "[γ]₂",
+            offset: G2_GAMMA,
+            source,
+        })) if matches!(*source, Error::Group(_))
+    ));
+    assert!(
+        Groth16VerifyingKey::from_bytes_with_validation(&groth16_key, KeyValidation::Trusted)
+            .is_ok()
+    );
+
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    assert!(matches!(
+        Groth16Verifier::verify(&bundle.raw_proof, &groth16_key, &bundle.public_inputs),
+        VerificationOutcome::Malformed { .. }
+    ));
+
+    let mut plonk_key = key(SP1ProofKind::Plonk);
+    let alpha_g2 = plonk_alpha_g2(&plonk_key);
+    plonk_key[alpha_g2..alpha_g2 + 64].copy_from_slice(&compressed_g2_outside_subgroup());
+    assert!(matches!(
+        PlonkVerifyingKey::from_bytes(&plonk_key),
+        Err(PlonkError::GeneralError(Error::InvalidFieldEncoding {
+            field: "KZG [α]G₂",
+            offset,
+            source,
+        })) if matches!(*source, Error::Group(_)) && offset == alpha_g2
+    ));
+    assert!(
+        PlonkVerifyingKey::from_bytes_with_validation(&plonk_key, KeyValidation::Trusted).is_ok()
+    );
+
+    let bundle = self::bundle("fibonacci", SP1ProofKind::Plonk);
+    assert!(matches!(
+        PlonkVerifier::verify(&bundle.raw_proof, &plonk_key, &bundle.public_inputs),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
+
+#[test]
+fn rejects_points_off_the_curve() {
+    let vk = Groth16VerifyingKey::from_bytes(&key(SP1ProofKind::Groth16)).unwrap();
+    let mut raw_key = vk
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    // Last byte of the y coordinate of [α]G₁
+    raw_key[G1_ALPHA + 63] ^= 1;
+    assert!(matches!(
+        Groth16VerifyingKey::from_bytes(&raw_key),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "[α]₁",
+            offset: G1_ALPHA,
+            source,
+        })) if matches!(*source, Error::Group(_))
+    ));
+    assert!(
+        Groth16VerifyingKey::from_bytes_with_validation(&raw_key, KeyValidation::Trusted).is_ok()
+    );
+}
+
+#[test]
+fn rejects_points_at_infinity() {
+    let mut groth16_key = key(SP1ProofKind::Groth16);
+    groth16_key[G2_DELTA..G2_DELTA + 64].copy_from_slice(&compressed_infinity());
+    assert!(matches!(
+        Groth16VerifyingKey::from_bytes(&groth16_key),
+        Err(Groth16Error::GeneralError(Error::InvalidFieldEncoding {
+            field: "[δ]₂",
+            offset: G2_DELTA,
+            source,
+        })) if matches!(*source, Error::UnexpectedIdentity)
+    ));
+
+    // Unlike the selectors, the permutation commitments are never at infinity
+    let mut plonk_key = key(SP1ProofKind::Plonk);
+    plonk_key[S1..S1 + 32].copy_from_slice(&compressed_infinity()[..32]);
+    assert!(matches!(
+        PlonkVerifyingKey::from_bytes(&plonk_key),
+        Err(PlonkError::GeneralError(Error::InvalidFieldEncoding {
+            field: "S1",
+            offset: S1,
+            source,
+        })) if matches!(*source, Error::UnexpectedIdentity)
+    ));
+    let bundle = bundle("fibonacci", SP1ProofKind::Plonk);
+    assert!(matches!(
+        PlonkVerifier::verify(&bundle.raw_proof, &plonk_key, &bundle.public_inputs),
+        VerificationOutcome::Malformed { .. }
+    ));
+
+    // G₂, [α]G₂ and G₁ of the KZG key of the PlonK key, without the pairing lines
+    let plonk_key = key(SP1ProofKind::Plonk);
+    let start = plonk_alpha_g2(&plonk_key) - 96;
+    let mut kzg_key = [
+        &plonk_key[start + 32..start + 160],
+        &plonk_key[start..start + 32],
+    ]
+    .concat();
+    assert!(KZGVerifyingKey::from_bytes(&kzg_key).is_ok());
+    kzg_key[128..].copy_from_slice(&compressed_infinity()[..32]);
+    assert!(matches!(
+        KZGVerifyingKey::from_bytes(&kzg_key),
+        Err(Error::InvalidFieldEncoding {
+            field: "KZG G₁",
+            offset: 128,
+            source,
+        }) if matches!(*source, Error::UnexpectedIdentity)
+    ));
+}
+
+// gnark leaves Qm at infinity for circuits without multiplication gates, and K points at infinity
+// for public inputs no constraint uses
+#[test]
+fn accepts_selectors_at_infinity() {
+    let mut plonk_key = key(SP1ProofKind::Plonk);
+    plonk_key[QM..QM + 32].copy_from_slice(&compressed_infinity()[..32]);
+    let vk = PlonkVerifyingKey::from_bytes(&plonk_key).unwrap();
+    assert_eq!(vk.to_bytes().unwrap(), plonk_key);
+    let raw_key = vk
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    assert_eq!(
+        PlonkVerifyingKey::from_bytes(&raw_key)
+            .unwrap()
+            .to_bytes()
+            .unwrap(),
+        plonk_key
+    );
+
+    // The proof was made for the SP1 circuit, so it fails the checks instead of being malformed
+    let bundle = bundle("fibonacci", SP1ProofKind::Plonk);
+    assert!(matches!(
+        PlonkVerifier::verify(&bundle.raw_proof, &plonk_key, &bundle.public_inputs),
+        VerificationOutcome::Invalid { .. }
+    ));
+
+    let mut groth16_key = key(SP1ProofKind::Groth16);
+    groth16_key[K..K + 32].copy_from_slice(&compressed_infinity()[..32]);
+    let vk = Groth16VerifyingKey::from_bytes(&groth16_key).unwrap();
+    assert_eq!(vk.to_bytes().unwrap(), groth16_key);
+
+    let bundle = self::bundle("fibonacci", SP1ProofKind::Groth16);
+    assert!(matches!(
+        Groth16Verifier::verify(&bundle.raw_proof, &groth16_key, &bundle.public_inputs),
+        VerificationOutcome::Invalid { .. }
+    ));
+}
+
+// Trusted keys skip the checks, but still read the point at infinity as the identity
+#[test]
+fn reads_trusted_g1_infinity_as_identity() {
+    let vk = Groth16VerifyingKey::from_bytes(&key(SP1ProofKind::Groth16)).unwrap();
+    for encoding in [PointEncoding::Compressed, PointEncoding::Uncompressed] {
+        let mut written = vk.to_bytes_with_encoding(encoding).unwrap();
+        match encoding {
+            PointEncoding::Compressed => {
+                written[G1_ALPHA..G1_ALPHA + 32].copy_from_slice(&compressed_infinity()[..32])
+            }
+            PointEncoding::Uncompressed => written[G1_ALPHA..G1_ALPHA + 64].fill(0),
+        }
+        let trusted =
+            Groth16VerifyingKey::from_bytes_with_validation(&written, KeyValidation::Trusted)
+                .unwrap();
+        assert_eq!(
+            trusted.to_bytes_with_encoding(encoding).unwrap(),
+            written,
+            "{:?}",
+            encoding
+        );
+    }
+}
+
+// gnark leaves the Pedersen key at infinity in keys of circuits without commitments
+#[test]
+fn accepts_unused_commitment_key_at_infinity() {
+    let mut groth16_key = key(SP1ProofKind::Groth16);
+    groth16_key[COMMITMENT_KEY..COMMITMENT_KEY + 64].copy_from_slice(&compressed_infinity());
+
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    assert_eq!(
+        Groth16Verifier::verify(&bundle.raw_proof, &groth16_key, &bundle.public_inputs),
+        VerificationOutcome::Valid
+    );
+}
+
+// gnark writes the point at infinity as all zeros in uncompressed keys
+#[test]
+fn reads_uncompressed_g2_infinity_as_identity() {
+    let vk = Groth16VerifyingKey::from_bytes(&key(SP1ProofKind::Groth16)).unwrap();
+    let mut raw_key = vk
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    // [δ]₂ follows [α]₁, [β]₁, [β]₂, [γ]₂ and [δ]₁
+    raw_key[448..576].fill(0);
+    let trusted =
+        Groth16VerifyingKey::from_bytes_with_validation(&raw_key, KeyValidation::Trusted).unwrap();
+    assert_eq!(
+        trusted
+            .to_bytes_with_encoding(PointEncoding::Uncompressed)
+            .unwrap(),
+        raw_key
+    );
+}
@@ -0,0 +1,275 @@
+mod common;
+
+use common::{bundle, key};
+use snark_bn254_verifier::{
+    kzg::KZGVerifyingKey, Error, Groth16Error, Groth16Proof, Groth16Verifier, Groth16VerifyingKey,
+    KeyValidation, PlonkError, PlonkProof, PlonkVerifier, PlonkVerifyingKey, PointEncoding,
+    SP1ProofKind, VerificationOutcome,
+};
+
+// xorshift64, so that the inputs are the same on every run
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+fn parse_all(bytes: &[u8]) {
+    let _ = Groth16Proof::from_bytes(bytes);
+    let _ = Groth16VerifyingKey::from_bytes_with_validation(bytes, KeyValidation::Trusted);
+    let _ = PlonkProof::from_bytes(bytes);
+    let _ = PlonkVerifyingKey::from_bytes_with_validation(bytes, KeyValidation::Trusted);
+    let _ = KZGVerifyingKey::from_bytes_with_validation(bytes, KeyValidation::Trusted);
+}
+
+#[test]
+fn rejects_every_truncated_proof() {
+    let groth16 = bundle("fibonacci", SP1ProofKind::Groth16);
+    let plonk = bundle("fibonacci", SP1ProofKind::Plonk);
+    for encoding in [PointEncoding::Uncompressed, PointEncoding::Compressed] {
+        let proof = Groth16Proof::from_bytes(&groth16.raw_proof)
+            .unwrap()
+            .to_bytes_with_encoding(encoding)
+            .unwrap();
+        for len in 0..proof.len() {
+            assert!(
+                Groth16Proof::from_bytes(&proof[..len]).is_err(),
+                "{:?} {}",
+                encoding,
+                len
+            );
+        }
+
+        let proof = PlonkProof::from_bytes(&plonk.raw_proof)
+            .unwrap()
+            .to_bytes_with_encoding(encoding)
+            .unwrap();
+        for len in 0..proof.len() {
+            assert!(
+                PlonkProof::from_bytes(&proof[..len]).is_err(),
+                "{:?} {}",
+                encoding,
+                len
+            );
+        }
+    }
+}
+
+#[test]
+fn rejects_truncated_keys() {
+    let groth16 = key(SP1ProofKind::Groth16);
+    for len in 0..groth16.len() {
+        assert!(
+            Groth16VerifyingKey::from_bytes_with_validation(
+                &groth16[..len],
+                KeyValidation::Trusted
+            )
+            .is_err(),
+            "{}",
+            len
+        );
+    }
+
+    // The pairing lines make up most of the PlonK key
+    let plonk = key(SP1ProofKind::Plonk);
+    for len in (0..plonk.len()).filter(|len| *len < 1024 || len % 1009 == 0 || *len > 34300) {
+        assert!(
+            PlonkVerifyingKey::from_bytes_with_validation(&plonk[..len], KeyValidation::Trusted)
+                .is_err(),
+            "{}",
+            len
+        );
+    }
+}
+
+#[test]
+fn reports_the_truncated_field() {
+    // The number of committed arrays follows the 3 K points of the SP1 Groth16 key
+    let groth16 = key(SP1ProofKind::Groth16);
+    match Groth16VerifyingKey::from_bytes(&groth16[..390]) {
+        Err(Groth16Error::GeneralError(Error::InsufficientBufferLength {
+            field,
+            offset,
+            expected,
+            got,
+        })) => {
+            assert_eq!(field, "number of committed public input arrays");
+            assert_eq!((offset, expected, got), (388, 392, 390));
+        }
+        other => panic!("{:?}", other),
+    }
+
+    // The shifted opening of Z follows the claimed values of an uncompressed proof
+    let plonk = bundle("fibonacci", SP1ProofKind::Plonk).raw_proof;
+    let num_claimed_values = u32::from_be_bytes(plonk[512..516].try_into().unwrap()) as usize;
+    let z_shifted_opening = 516 + 32 * num_claimed_values;
+    match PlonkProof::from_bytes(&plonk[..z_shifted_opening + 10]) {
+        Err(PlonkError::GeneralError(Error::InsufficientBufferLength {
+            field, offset, ..
+        })) => {
+            assert_eq!(field, "shifted opening proof of Z");
+            assert_eq!(offset, z_shifted_opening);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn reports_the_field_of_undecodable_values() {
+    // y coordinate of Z in a Solidity PlonK proof, after L, R, O, H1, H2, H3 and 5 claimed values
+    let bundle = bundle("fibonacci", SP1ProofKind::Plonk);
+    let vk = PlonkVerifyingKey::from_bytes(&key(SP1ProofKind::Plonk)).unwrap();
+    let mut proof = bundle.encoded_proof.clone();
+    proof[0x25f] ^= 1;
+    match PlonkProof::from_solidity_bytes(&proof, &vk, &bundle.public_inputs) {
+        Err(PlonkError::GeneralError(e @ Error::InvalidFieldEncoding { .. })) => {
+            assert_eq!(e.to_string(), "Invalid Z at offset 544: BN254 Group Error");
+        }
+        other => panic!("{:?}", other),
+    }
+
+    // The Solidity PlonK proof ends with the BSB22 commitments
+    let proof = &bundle.encoded_proof;
+    match PlonkProof::from_solidity_bytes(&proof[..proof.len() - 1], &vk, &bundle.public_inputs) {
+        Err(PlonkError::GeneralError(Error::InsufficientBufferLength {
+            field, offset, ..
+        })) => {
+            assert_eq!(field, "BSB22 commitment");
+            assert_eq!(offset, proof.len() - 64);
+        }
+        other => panic!("{:?}", other),
+    }
+    assert!(matches!(
+        PlonkProof::from_solidity_bytes(&[proof, &[0][..]].concat(), &vk, &bundle.public_inputs),
+        Err(PlonkError::GeneralError(Error::TrailingBytes {
+            field: "Solidity proof",
+            ..
+        }))
+    ));
+}
+
+#[test]
+fn rejects_trailing_bytes() {
+    let trailing = |bytes: &[u8]| [bytes, &[1, 2, 3]].concat();
+    let trailing_field = |error: Error| match error {
+        Error::TrailingBytes { field, .. } => field,
+        other => panic!("{:?}", other),
+    };
+
+    let groth16 = bundle("fibonacci", SP1ProofKind::Groth16);
+    match Groth16Proof::from_bytes(&trailing(&groth16.raw_proof)) {
+        Err(Groth16Error::GeneralError(e)) => assert_eq!(trailing_field(e), "Groth16 proof"),
+        other => panic!("{:?}", other),
+    }
+    match Groth16VerifyingKey::from_bytes(&trailing(&key(SP1ProofKind::Groth16))) {
+        Err(Groth16Error::GeneralError(e)) => {
+            assert_eq!(trailing_field(e), "Groth16 verifying key")
+        }
+        other => panic!("{:?}", other),
+    }
+
+    let plonk = bundle("fibonacci", SP1ProofKind::Plonk);
+    match PlonkProof::from_bytes(&trailing(&plonk.raw_proof)) {
+        Err(PlonkError::GeneralError(e)) => assert_eq!(trailing_field(e), "PlonK proof"),
+        other => panic!("{:?}", other),
+    }
+    match PlonkVerifyingKey::from_bytes(&trailing(&key(SP1ProofKind::Plonk))) {
+        Err(PlonkError::GeneralError(e)) => assert_eq!(trailing_field(e), "PlonK verifying key"),
+        other => panic!("{:?}", other),
+    }
+
+    // Verification reports them as malformed instead of ignoring them
+    assert!(matches!(
+        Groth16Verifier::verify(
+            &groth16.raw_proof,
+            &trailing(&key(SP1ProofKind::Groth16)),
+            &groth16.public_inputs
+        ),
+        VerificationOutcome::Malformed { .. }
+    ));
+    assert!(matches!(
+        PlonkVerifier::verify(
+            &trailing(&plonk.raw_proof),
+            &key(SP1ProofKind::Plonk),
+            &plonk.public_inputs
+        ),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
+
+#[test]
+fn rejects_inconsistent_counts() {
+    // One commitment constraint index per Qcp
+    let mut plonk = key(SP1ProofKind::Plonk);
+    let num_qcp = u32::from_be_bytes(plonk[368..372].try_into().unwrap()) as usize;
+    let count = plonk.len() - 4 - 8 * num_qcp;
+    plonk[count..count + 4].copy_from_slice(&(num_qcp as u32 + 1).to_be_bytes());
+    plonk.extend_from_slice(&[0u8; 8]);
+    assert!(matches!(
+        PlonkVerifyingKey::from_bytes(&plonk),
+        Err(PlonkError::GeneralError(Error::InvalidField { field: "number of commitment constraint indexes", offset }))
+            if offset == count
+    ));
+
+    // A PlonK proof without claimed values parses, but cannot be verified
+    let bundle = bundle("fibonacci", SP1ProofKind::Plonk);
+    let mut proof = bundle.raw_proof[..512].to_vec();
+    proof.extend_from_slice(&0u32.to_be_bytes());
+    let num_claimed_values = u32::from_be_bytes(bundle.raw_proof[512..516].try_into().unwrap());
+    proof.extend_from_slice(&bundle.raw_proof[516 + 32 * num_claimed_values as usize..]);
+    assert!(PlonkProof::from_bytes(&proof).is_ok());
+    assert!(matches!(
+        PlonkVerifier::verify(&proof, &key(SP1ProofKind::Plonk), &bundle.public_inputs),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
+
+#[test]
+fn never_panics_on_arbitrary_bytes() {
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..2000 {
+        let len = rng.below(1200);
+        let mut bytes = rng.bytes(len);
+        // Counts are read as u32, so small ones reach the loops over their elements
+        if len > 300 {
+            let at = rng.below(len - 4);
+            bytes[at..at + 4].copy_from_slice(&(rng.below(8) as u32).to_be_bytes());
+        }
+        parse_all(&bytes);
+    }
+}
+
+#[test]
+fn never_panics_on_corrupted_proofs() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let groth16 = bundle("fibonacci", SP1ProofKind::Groth16);
+    let plonk = bundle("fibonacci", SP1ProofKind::Plonk);
+    let groth16_key = key(SP1ProofKind::Groth16);
+    let plonk_key = key(SP1ProofKind::Plonk);
+
+    for _ in 0..100 {
+        let mut proof = groth16.raw_proof.clone();
+        let at = rng.below(proof.len());
+        proof[at] ^= 1 << rng.below(8);
+        assert!(!Groth16Verifier::verify(&proof, &groth16_key, &groth16.public_inputs).is_valid());
+
+        let mut proof = plonk.raw_proof.clone();
+        let at = rng.below(proof.len());
+        proof[at] ^= 1 << rng.below(8);
+        proof.truncate(proof.len() - rng.below(2) * rng.below(64));
+        assert!(!PlonkVerifier::verify(&proof, &plonk_key, &plonk.public_inputs).is_valid());
+    }
+}
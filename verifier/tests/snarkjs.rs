@@ -0,0 +1,103 @@
+mod common;
+
+use common::read_snarkjs;
+use serde_json::Value;
+use snark_bn254_verifier::{
+    snarkjs_public_inputs, Fr, Groth16Error, Groth16Proof, Groth16Verifier, Groth16VerifyingKey,
+    VerificationOutcome,
+};
+
+fn edit(json: &str, f: impl FnOnce(&mut Value)) -> String {
+    let mut value: Value = serde_json::from_str(json).unwrap();
+    f(&mut value);
+    value.to_string()
+}
+
+#[test]
+fn verifies_snarkjs_proof() {
+    assert_eq!(
+        Groth16Verifier::verify_snarkjs(
+            &read_snarkjs("proof"),
+            &read_snarkjs("verification_key"),
+            &read_snarkjs("public")
+        ),
+        VerificationOutcome::Valid
+    );
+
+    let proof = Groth16Proof::from_snarkjs_json(&read_snarkjs("proof")).unwrap();
+    let vk = Groth16VerifyingKey::from_snarkjs_json(&read_snarkjs("verification_key")).unwrap();
+    let public_inputs = snarkjs_public_inputs(&read_snarkjs("public")).unwrap();
+    assert_eq!(
+        public_inputs,
+        vec![Fr::from_str("33").unwrap(), Fr::from_str("3").unwrap()]
+    );
+    assert!(Groth16Verifier::verify_parsed(&proof, &vk, &public_inputs).is_valid());
+}
+
+#[test]
+fn rejects_other_public_signals() {
+    assert!(matches!(
+        Groth16Verifier::verify_snarkjs(
+            &read_snarkjs("proof"),
+            &read_snarkjs("verification_key"),
+            "[\"33\", \"4\"]"
+        ),
+        VerificationOutcome::Invalid { .. }
+    ));
+    assert!(matches!(
+        Groth16Verifier::verify_snarkjs(
+            &read_snarkjs("proof"),
+            &read_snarkjs("verification_key"),
+            "[\"33\"]"
+        ),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
+
+#[test]
+fn reads_g2_coordinates_in_snarkjs_order() {
+    // Swapping c0 and c1, as in the Solidity calldata encoding, does not give a point on the curve
+    let swapped = edit(&read_snarkjs("proof"), |proof| {
+        for coordinate in 0..2 {
+            proof["pi_b"][coordinate].as_array_mut().unwrap().reverse();
+        }
+    });
+    assert!(matches!(
+        Groth16Proof::from_snarkjs_json(&swapped),
+        Err(Groth16Error::InvalidSnarkjsJson(_))
+    ));
+}
+
+#[test]
+fn rejects_malformed_snarkjs_json() {
+    let vk = read_snarkjs("verification_key");
+
+    let plonk = edit(&vk, |vk| vk["protocol"] = "plonk".into());
+    assert!(matches!(
+        Groth16VerifyingKey::from_snarkjs_json(&plonk),
+        Err(Groth16Error::InvalidSnarkjsJson(_))
+    ));
+
+    let n_public = edit(&vk, |vk| vk["nPublic"] = 3.into());
+    assert!(matches!(
+        Groth16VerifyingKey::from_snarkjs_json(&n_public),
+        Err(Groth16Error::InvalidSnarkjsJson(_))
+    ));
+
+    // The base field modulus is not a canonical encoding of zero
+    let not_reduced = edit(&read_snarkjs("proof"), |proof| {
+        proof["pi_a"][0] =
+            "21888242871839275222246405745257275088696311157297823662689037894645226208583".into();
+    });
+    assert!(Groth16Proof::from_snarkjs_json(&not_reduced).is_err());
+
+    let projective = edit(&read_snarkjs("proof"), |proof| {
+        proof["pi_c"][2] = "2".into()
+    });
+    assert!(Groth16Proof::from_snarkjs_json(&projective).is_err());
+
+    assert!(matches!(
+        Groth16Verifier::verify_snarkjs("{}", &vk, &read_snarkjs("public")),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::{bundle, bundle_bytes, read, PROGRAMS};
+use snark_bn254_verifier::{
+    committed_values_digest, Groth16Verifier, PlonkVerifier, SP1Error, SP1ProofBundle,
+    SP1ProofKind, VerificationOutcome,
+};
+
+#[test]
+fn decodes_and_verifies_groth16_bundles() {
+    let vk = read("vk/v2.0.0/groth16_vk.bin");
+    for program in PROGRAMS {
+        let bundle = bundle(program, SP1ProofKind::Groth16);
+        assert_eq!(bundle.kind, SP1ProofKind::Groth16, "{}", program);
+        assert_eq!(bundle.sp1_version, "v2.0.0", "{}", program);
+        assert_eq!(bundle.encoded_proof, bundle.raw_proof[..256], "{}", program);
+        assert_eq!(
+            Groth16Verifier::verify(&bundle.raw_proof, &vk, &bundle.public_inputs),
+            VerificationOutcome::Valid,
+            "{}",
+            program
+        );
+    }
+}
+
+#[test]
+fn decodes_and_verifies_plonk_bundles() {
+    let vk = read("vk/v2.0.0/plonk_vk.bin");
+    for program in PROGRAMS {
+        let bundle = bundle(program, SP1ProofKind::Plonk);
+        assert_eq!(bundle.kind, SP1ProofKind::Plonk, "{}", program);
+        assert_eq!(bundle.sp1_version, "v2.0.0", "{}", program);
+        assert_eq!(
+            PlonkVerifier::verify(&bundle.raw_proof, &vk, &bundle.public_inputs),
+            VerificationOutcome::Valid,
+            "{}",
+            program
+        );
+    }
+}
+
+#[test]
+fn reads_public_values() {
+    let fibonacci = bundle("fibonacci", SP1ProofKind::Plonk);
+    assert_eq!(fibonacci.public_values.len(), 96);
+
+    let is_prime = bundle("is-prime", SP1ProofKind::Groth16);
+    assert_eq!(is_prime.public_values.len(), 1);
+}
+
+#[test]
+fn rejects_malformed_bundles() {
+    let bytes = bundle_bytes("fibonacci", SP1ProofKind::Groth16);
+
+    assert!(matches!(
+        SP1ProofBundle::from_bytes(&bytes[..bytes.len() - 1]),
+        Err(SP1Error::UnexpectedEnd)
+    ));
+
+    let mut trailing = bytes.clone();
+    trailing.push(0);
+    assert!(matches!(
+        SP1ProofBundle::from_bytes(&trailing),
+        Err(SP1Error::TrailingBytes)
+    ));
+
+    let mut compressed = bytes;
+    compressed[..4].copy_from_slice(&1u32.to_le_bytes());
+    assert!(matches!(
+        SP1ProofBundle::from_bytes(&compressed),
+        Err(SP1Error::UnsupportedProofVariant(1))
+    ));
+}
+
+#[test]
+fn public_values_match_committed_values_digest() {
+    for program in PROGRAMS {
+        for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+            let bundle = bundle(program, kind);
+            assert_eq!(
+                committed_values_digest(&bundle.public_values),
+                bundle.public_inputs[1],
+                "{} {:?}",
+                program,
+                kind
+            );
+            bundle.check_public_values().unwrap();
+        }
+    }
+}
+
+#[test]
+fn rejects_tampered_public_values() {
+    let mut bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    bundle.public_values[0] ^= 1;
+    assert!(matches!(
+        bundle.check_public_values(),
+        Err(SP1Error::PublicValuesDigestMismatch)
+    ));
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use common::{bundle, PROGRAM_VKEY_HASHES};
+use snark_bn254_verifier::{
+    SP1Calldata, SP1Error, SP1ProofBundle, SP1ProofKind, SP1Verifier, VerificationOutcome,
+    VERIFY_PROOF_SELECTOR,
+};
+
+fn abi_word(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+fn abi_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = abi_word(bytes.len()).to_vec();
+    encoded.extend_from_slice(bytes);
+    encoded.resize(32 + bytes.len().div_ceil(32) * 32, 0);
+    encoded
+}
+
+// Calldata of `verifyProof(programVKey, publicValues, proofBytes)`, where the proof bytes are the
+// circuit verifying key selector followed by the proof encoded for the Solidity verifier, as
+// returned by `SP1ProofWithPublicValues::bytes`
+fn calldata(program_vkey: &str, public_values: &[u8], bundle: &SP1ProofBundle) -> Vec<u8> {
+    let mut proof_bytes = bundle.circuit_vkey_hash[..4].to_vec();
+    proof_bytes.extend_from_slice(&bundle.encoded_proof);
+
+    let public_values = abi_bytes(public_values);
+    let mut calldata = VERIFY_PROOF_SELECTOR.to_vec();
+    calldata.extend_from_slice(&hex::decode(program_vkey.trim_start_matches("0x")).unwrap());
+    calldata.extend_from_slice(&abi_word(96));
+    calldata.extend_from_slice(&abi_word(96 + public_values.len()));
+    calldata.extend_from_slice(&public_values);
+    calldata.extend_from_slice(&abi_bytes(&proof_bytes));
+    calldata
+}
+
+#[test]
+fn decodes_gateway_calldata() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    let (_, program_vkey) = PROGRAM_VKEY_HASHES[0];
+    let encoded = calldata(program_vkey, &bundle.public_values, &bundle);
+
+    let decoded = SP1Calldata::from_hex(&format!("0x{}", hex::encode(&encoded))).unwrap();
+    assert_eq!(
+        format!("0x{}", hex::encode(decoded.program_vkey)),
+        program_vkey
+    );
+    assert_eq!(decoded.public_values, bundle.public_values);
+    assert_eq!(decoded.verifier_selector, bundle.circuit_vkey_hash[..4]);
+    assert_eq!(decoded.proof, bundle.encoded_proof);
+    assert_eq!(decoded.public_inputs().unwrap(), bundle.public_inputs);
+    assert_eq!(decoded.circuit_key().unwrap().kind, SP1ProofKind::Groth16);
+}
+
+#[test]
+fn verifies_gateway_calldata() {
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        for (program, program_vkey) in PROGRAM_VKEY_HASHES {
+            let bundle = bundle(program, kind);
+            let calldata = calldata(program_vkey, &bundle.public_values, &bundle);
+            assert_eq!(
+                SP1Verifier::verify_calldata(&SP1Calldata::from_bytes(&calldata).unwrap()),
+                VerificationOutcome::Valid,
+                "{} {:?}",
+                program,
+                kind
+            );
+        }
+    }
+}
+
+#[test]
+fn encodes_bundles_as_gateway_calldata() {
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        for (program, program_vkey) in PROGRAM_VKEY_HASHES {
+            let bundle = bundle(program, kind);
+            let encoded = SP1Calldata::from_bundle(&bundle);
+            assert_eq!(
+                encoded.to_bytes(),
+                calldata(program_vkey, &bundle.public_values, &bundle),
+                "{} {:?}",
+                program,
+                kind
+            );
+
+            let decoded = SP1Calldata::from_hex(&encoded.to_hex()).unwrap();
+            assert_eq!(
+                SP1Verifier::verify_calldata(&decoded),
+                VerificationOutcome::Valid,
+                "{} {:?}",
+                program,
+                kind
+            );
+        }
+    }
+}
+
+#[test]
+fn rejects_other_public_values_and_programs() {
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        let bundle = bundle("fibonacci", kind);
+
+        let mut public_values = bundle.public_values.clone();
+        public_values[0] ^= 1;
+        let tampered = calldata(PROGRAM_VKEY_HASHES[0].1, &public_values, &bundle);
+        assert!(
+            matches!(
+                SP1Verifier::verify_calldata(&SP1Calldata::from_bytes(&tampered).unwrap()),
+                VerificationOutcome::Invalid { .. }
+            ),
+            "{:?}",
+            kind
+        );
+
+        let other_program = calldata(PROGRAM_VKEY_HASHES[1].1, &bundle.public_values, &bundle);
+        assert!(
+            !SP1Verifier::verify_calldata(&SP1Calldata::from_bytes(&other_program).unwrap())
+                .is_valid(),
+            "{:?}",
+            kind
+        );
+    }
+}
+
+#[test]
+fn rejects_malformed_calldata() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Plonk);
+    let encoded = calldata(PROGRAM_VKEY_HASHES[0].1, &bundle.public_values, &bundle);
+
+    let mut other_function = encoded.clone();
+    other_function[0] ^= 1;
+    assert!(matches!(
+        SP1Calldata::from_bytes(&other_function),
+        Err(SP1Error::UnexpectedFunctionSelector)
+    ));
+
+    for len in [4, 100, encoded.len() - 32] {
+        assert!(
+            matches!(
+                SP1Calldata::from_bytes(&encoded[..len]),
+                Err(SP1Error::InvalidCalldata)
+            ),
+            "{}",
+            len
+        );
+    }
+
+    let mut huge_offset = encoded.clone();
+    huge_offset[4 + 32 + 8] = 1;
+    assert!(matches!(
+        SP1Calldata::from_bytes(&huge_offset),
+        Err(SP1Error::InvalidCalldata)
+    ));
+
+    assert!(matches!(
+        SP1Calldata::from_hex("0x4149zz"),
+        Err(SP1Error::InvalidHex)
+    ));
+
+    let mut unknown_verifier = SP1Calldata::from_bytes(&encoded).unwrap();
+    unknown_verifier.verifier_selector = [0; 4];
+    assert!(matches!(
+        unknown_verifier.circuit_key(),
+        Err(SP1Error::UnknownVerifierSelector(_))
+    ));
+    assert!(matches!(
+        SP1Verifier::verify_calldata(&unknown_verifier),
+        VerificationOutcome::Malformed { .. }
+    ));
+
+    let mut truncated_proof = SP1Calldata::from_bytes(&encoded).unwrap();
+    truncated_proof.proof.pop();
+    assert!(matches!(
+        SP1Verifier::verify_calldata(&truncated_proof),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
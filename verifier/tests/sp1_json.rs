@@ -0,0 +1,124 @@
+mod common;
+
+use common::{bundle, json, PROGRAMS};
+use snark_bn254_verifier::{
+    SP1Error, SP1ProofJson, SP1ProofKind, SP1Verifier, VerificationOutcome,
+};
+
+#[test]
+fn parses_the_same_proof_as_the_bundle() {
+    for program in PROGRAMS {
+        for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+            let proof = SP1ProofJson::from_json(&json(program, kind)).unwrap();
+            let bundle = bundle(program, kind);
+            assert_eq!(proof.raw_proof, bundle.raw_proof, "{} {:?}", program, kind);
+            assert_eq!(
+                proof.public_inputs, bundle.public_inputs,
+                "{} {:?}",
+                program, kind
+            );
+            // Written before the example script stored the circuit verifying key hash
+            assert_eq!(proof.circuit_vkey_hash, None, "{} {:?}", program, kind);
+        }
+    }
+}
+
+#[test]
+fn verifies_json_proofs() {
+    for program in PROGRAMS {
+        for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+            assert_eq!(
+                SP1Verifier::verify_json(&json(program, kind), kind),
+                VerificationOutcome::Valid,
+                "{} {:?}",
+                program,
+                kind
+            );
+        }
+    }
+}
+
+#[test]
+fn rejects_inconsistent_json() {
+    let json = json("fibonacci", SP1ProofKind::Groth16);
+
+    let tampered = json.replace(
+        "\"committed_values_digest\": \"0",
+        "\"committed_values_digest\": \"1",
+    );
+    assert_ne!(tampered, json);
+    assert!(matches!(
+        SP1ProofJson::from_json(&tampered),
+        Err(SP1Error::JsonPublicInputsMismatch)
+    ));
+
+    assert!(matches!(
+        SP1ProofJson::from_json("{\"raw_proof\": \"00\"}"),
+        Err(SP1Error::InvalidJson(_))
+    ));
+    assert!(matches!(
+        SP1ProofJson::from_json("not json"),
+        Err(SP1Error::InvalidJson(_))
+    ));
+    assert!(matches!(
+        SP1Verifier::verify_json(&tampered, SP1ProofKind::Groth16),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
+
+// Adds the circuit verifying key hash the example script now writes
+fn with_circuit_vkey_hash(json: &str, circuit_vkey_hash: &str) -> String {
+    json.replacen(
+        "{",
+        &format!("{{\n  \"circuit_vkey_hash\": \"{}\",", circuit_vkey_hash),
+        1,
+    )
+}
+
+#[test]
+fn picks_the_circuit_key_of_the_json() {
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        let bundle = bundle("fibonacci", kind);
+        let json = with_circuit_vkey_hash(
+            &json("fibonacci", kind),
+            &hex::encode(bundle.circuit_vkey_hash),
+        );
+        let proof = SP1ProofJson::from_json(&json).unwrap();
+        assert_eq!(proof.circuit_vkey_hash, Some(bundle.circuit_vkey_hash));
+        assert_eq!(
+            proof.circuit_key(kind).unwrap(),
+            bundle.circuit_key().unwrap()
+        );
+        assert_eq!(
+            SP1Verifier::verify_json(&json, kind),
+            VerificationOutcome::Valid,
+            "{:?}",
+            kind
+        );
+    }
+}
+
+#[test]
+fn rejects_json_of_unknown_circuit_keys() {
+    let json = json("fibonacci", SP1ProofKind::Groth16);
+
+    let unknown = with_circuit_vkey_hash(&json, &"00".repeat(32));
+    assert!(matches!(
+        SP1Verifier::verify_json(&unknown, SP1ProofKind::Groth16),
+        VerificationOutcome::Malformed { .. }
+    ));
+    // A Groth16 proof has no PlonK circuit key
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    let known = with_circuit_vkey_hash(&json, &hex::encode(bundle.circuit_vkey_hash));
+    assert!(matches!(
+        SP1Verifier::verify_json(&known, SP1ProofKind::Plonk),
+        VerificationOutcome::Malformed { .. }
+    ));
+
+    for circuit_vkey_hash in ["1234", "not hex"] {
+        assert!(matches!(
+            SP1ProofJson::from_json(&with_circuit_vkey_hash(&json, circuit_vkey_hash)),
+            Err(SP1Error::InvalidHex)
+        ));
+    }
+}
@@ -0,0 +1,236 @@
+mod common;
+
+use common::{bundle, PROGRAM_VKEY_HASHES};
+use sha2::{Digest, Sha256};
+use snark_bn254_verifier::{
+    Groth16VerifyingKey, PointEncoding, SP1CircuitKey, SP1Error, SP1ProofKind, SP1Verifier,
+    VerificationOutcome,
+};
+
+#[test]
+fn verifies_proofs_of_the_expected_program() {
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        for (program, vkey_hash) in PROGRAM_VKEY_HASHES {
+            let bundle = bundle(program, kind);
+            assert_eq!(
+                SP1Verifier::verify(&bundle.raw_proof, &bundle.public_values, vkey_hash, kind),
+                VerificationOutcome::Valid,
+                "{} {:?}",
+                program,
+                kind
+            );
+            assert_eq!(
+                SP1Verifier::verify_onchain(
+                    &bundle.bytes(),
+                    &bundle.public_values,
+                    vkey_hash,
+                    kind
+                ),
+                VerificationOutcome::Valid,
+                "{} {:?}",
+                program,
+                kind
+            );
+        }
+    }
+}
+
+#[test]
+fn rejects_proofs_of_other_programs() {
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        let bundle = bundle("fibonacci", kind);
+        let (_, other_vkey_hash) = PROGRAM_VKEY_HASHES[1];
+        let outcome = SP1Verifier::verify(
+            &bundle.raw_proof,
+            &bundle.public_values,
+            other_vkey_hash,
+            kind,
+        );
+        assert!(
+            matches!(outcome, VerificationOutcome::Invalid { .. }),
+            "{:?}",
+            outcome
+        );
+    }
+}
+
+#[test]
+fn rejects_tampered_public_values() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    let (_, vkey_hash) = PROGRAM_VKEY_HASHES[0];
+    let mut public_values = bundle.public_values.clone();
+    public_values[0] ^= 1;
+    let outcome = SP1Verifier::verify(
+        &bundle.raw_proof,
+        &public_values,
+        vkey_hash,
+        SP1ProofKind::Groth16,
+    );
+    assert!(
+        matches!(outcome, VerificationOutcome::Invalid { .. }),
+        "{:?}",
+        outcome
+    );
+}
+
+#[test]
+fn rejects_malformed_program_vkey_hashes() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    for vkey_hash in ["0x1234", "not hex", &format!("0x{}", "ff".repeat(32))] {
+        let outcome = SP1Verifier::verify(
+            &bundle.raw_proof,
+            &bundle.public_values,
+            vkey_hash,
+            SP1ProofKind::Groth16,
+        );
+        assert!(
+            matches!(outcome, VerificationOutcome::Malformed { .. }),
+            "{:?}",
+            outcome
+        );
+    }
+}
+
+#[test]
+fn rejects_unknown_verifier_selectors() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    let (_, vkey_hash) = PROGRAM_VKEY_HASHES[0];
+    let mut proof = bundle.bytes();
+    proof[0] ^= 1;
+    for proof in [&proof[..], &proof[..3]] {
+        let outcome = SP1Verifier::verify_onchain(
+            proof,
+            &bundle.public_values,
+            vkey_hash,
+            SP1ProofKind::Groth16,
+        );
+        assert!(
+            matches!(outcome, VerificationOutcome::Malformed { .. }),
+            "{:?}",
+            outcome
+        );
+    }
+
+    // The selector of a Groth16 proof matches no PlonK key
+    let outcome = SP1Verifier::verify_onchain(
+        &bundle.bytes(),
+        &bundle.public_values,
+        vkey_hash,
+        SP1ProofKind::Plonk,
+    );
+    assert!(
+        matches!(outcome, VerificationOutcome::Malformed { .. }),
+        "{:?}",
+        outcome
+    );
+}
+
+#[test]
+fn picks_the_circuit_key_of_the_verifier_selector() {
+    let bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    let (_, vkey_hash) = PROGRAM_VKEY_HASHES[0];
+    let v2 = SP1CircuitKey::latest(SP1ProofKind::Groth16);
+
+    // A newer release with another key: the uncompressed v2.0.0 key with [α]₁ replaced by [β]₁
+    let mut bytes = Groth16VerifyingKey::from_bytes(v2.bytes)
+        .unwrap()
+        .to_bytes_with_encoding(PointEncoding::Uncompressed)
+        .unwrap();
+    bytes.copy_within(64..128, 0);
+    let v3 = SP1CircuitKey {
+        version: "v3.0.0",
+        kind: SP1ProofKind::Groth16,
+        bytes: Box::leak(bytes.into_boxed_slice()),
+    };
+    let keys = [*v2, v3];
+
+    assert_eq!(
+        SP1Verifier::verify_onchain_with_keys(
+            &bundle.bytes(),
+            &bundle.public_values,
+            vkey_hash,
+            SP1ProofKind::Groth16,
+            &keys,
+        ),
+        VerificationOutcome::Valid
+    );
+
+    // The same proof routed to the newer key
+    let v3_hash = Sha256::digest(v3.bytes);
+    assert_eq!(v3.hash()[..], v3_hash[..]);
+    let proof = [&v3_hash[..4], &bundle.encoded_proof[..]].concat();
+    let outcome = SP1Verifier::verify_onchain_with_keys(
+        &proof,
+        &bundle.public_values,
+        vkey_hash,
+        SP1ProofKind::Groth16,
+        &keys,
+    );
+    assert!(
+        matches!(outcome, VerificationOutcome::Invalid { .. }),
+        "{:?}",
+        outcome
+    );
+
+    let outcome = SP1Verifier::verify_onchain_with_keys(
+        &bundle.bytes(),
+        &bundle.public_values,
+        vkey_hash,
+        SP1ProofKind::Groth16,
+        &[v3],
+    );
+    assert!(
+        matches!(outcome, VerificationOutcome::Malformed { .. }),
+        "{:?}",
+        outcome
+    );
+}
+
+#[test]
+fn finds_keys_by_version_and_hash_prefix() {
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        let key = SP1CircuitKey::by_version(kind, "v2.0.0").unwrap();
+        assert_eq!(key.kind, kind);
+        assert_eq!(SP1CircuitKey::latest(kind), key);
+        assert_eq!(
+            SP1CircuitKey::by_hash_prefix(kind, &key.hash()[..4]),
+            Some(key)
+        );
+        assert_eq!(SP1CircuitKey::by_hash_prefix(kind, &key.hash()), Some(key));
+        assert_eq!(SP1CircuitKey::by_hash_prefix(kind, &[]), None);
+        assert_eq!(SP1CircuitKey::by_version(kind, "v0.0.0"), None);
+    }
+}
+
+#[test]
+fn picks_the_key_a_bundle_was_generated_for() {
+    let (_, vkey_hash) = PROGRAM_VKEY_HASHES[0];
+    for kind in [SP1ProofKind::Groth16, SP1ProofKind::Plonk] {
+        let bundle = bundle("fibonacci", kind);
+        let key = bundle.circuit_key().unwrap();
+        assert_eq!(key.hash(), bundle.circuit_vkey_hash);
+        assert_eq!(key.kind, kind);
+        assert_eq!(
+            SP1Verifier::verify_bundle(&bundle, vkey_hash),
+            VerificationOutcome::Valid
+        );
+    }
+}
+
+#[test]
+fn falls_back_to_the_sp1_version() {
+    let (_, vkey_hash) = PROGRAM_VKEY_HASHES[0];
+    let mut bundle = bundle("fibonacci", SP1ProofKind::Groth16);
+    bundle.circuit_vkey_hash = [0; 32];
+    assert_eq!(bundle.circuit_key().unwrap().version, "v2.0.0");
+
+    bundle.sp1_version = "v0.0.0".to_string();
+    assert!(matches!(
+        bundle.circuit_key(),
+        Err(SP1Error::UnknownCircuitKey(_))
+    ));
+    assert!(matches!(
+        SP1Verifier::verify_bundle(&bundle, vkey_hash),
+        VerificationOutcome::Malformed { .. }
+    ));
+}
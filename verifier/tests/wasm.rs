@@ -0,0 +1,153 @@
+#![cfg(feature = "wasm")]
+
+mod common;
+
+use common::{bundle, bundle_bytes, json, kind_name, read, PROGRAMS, PROGRAM_VKEY_HASHES};
+use snark_bn254_verifier::{
+    verify_groth16_proof_batch, verify_groth16_proof_with_inputs, verify_json,
+    verify_plonk_proof_with_inputs, verify_proof, verify_sp1, verify_sp1_onchain, ProofMode,
+    SP1ProofBundle, SP1ProofKind,
+};
+
+// Replaces the G₁ point at `offset` of the hex-encoded gnark proof inside a serialized bundle by
+// the first one, a valid point that fails the pairing check: Krs of a Groth16 proof at 192, the
+// batched opening proof of a PlonK proof at 448
+fn corrupt_proof(contents: &mut [u8], offset: usize) {
+    let raw_proof = hex::encode(SP1ProofBundle::from_bytes(contents).unwrap().raw_proof);
+    let start = contents
+        .windows(raw_proof.len())
+        .position(|window| window == raw_proof.as_bytes())
+        .unwrap();
+    contents.copy_within(start..start + 128, start + 2 * offset);
+}
+
+#[test]
+fn verifies_sp1_proofs() {
+    for (kind, mode, offset) in [
+        (SP1ProofKind::Groth16, ProofMode::Groth16, 192),
+        (SP1ProofKind::Plonk, ProofMode::Plonk, 448),
+    ] {
+        let contents = bundle_bytes("fibonacci", kind);
+        let result = verify_proof(&contents, mode);
+        assert_eq!(result.status(), "valid", "{:?}", kind);
+        assert!(result.is_valid());
+        let bundle = SP1ProofBundle::from_bytes(&contents).unwrap();
+        assert_eq!(result.public_values(), Some(bundle.public_values));
+
+        let mut corrupted = contents.clone();
+        corrupt_proof(&mut corrupted, offset);
+        let result = verify_proof(&corrupted, mode);
+        assert_eq!(result.status(), "invalid", "{:?}", kind);
+        assert!(!result.is_valid());
+        assert!(result.message().is_some());
+        assert_eq!(result.public_values(), None);
+
+        let result = verify_proof(&contents[..contents.len() - 1], mode);
+        assert_eq!(result.status(), "malformed", "{:?}", kind);
+        assert_eq!(result.public_values(), None);
+    }
+
+    // A PlonK proof read as a Groth16 one
+    let result = verify_proof(
+        &bundle_bytes("fibonacci", SP1ProofKind::Plonk),
+        ProofMode::Groth16,
+    );
+    assert_eq!(result.status(), "malformed");
+}
+
+#[test]
+fn verifies_batches_of_sp1_groth16_proofs() {
+    assert!(verify_groth16_proof_batch(&[]).is_empty());
+
+    let contents: Vec<_> = PROGRAMS
+        .iter()
+        .map(|p| bundle_bytes(p, SP1ProofKind::Groth16))
+        .collect();
+    let results = verify_groth16_proof_batch(&[&contents[0]]);
+    assert_eq!(results.len(), 1);
+    assert!(results[0].is_valid());
+
+    let mut corrupted = contents[2].clone();
+    corrupt_proof(&mut corrupted, 192);
+    let batch = [
+        contents[0].as_slice(),
+        contents[1].as_slice(),
+        corrupted.as_slice(),
+        contents[3].as_slice(),
+        &contents[3][..10],
+    ];
+    let results = verify_groth16_proof_batch(&batch);
+    let statuses: Vec<_> = results.iter().map(|result| result.status()).collect();
+    assert_eq!(
+        statuses,
+        ["valid", "valid", "invalid", "valid", "malformed"]
+    );
+    let bundle = SP1ProofBundle::from_bytes(&contents[0]).unwrap();
+    assert_eq!(results[0].public_values(), Some(bundle.public_values));
+    assert_eq!(results[2].public_values(), None);
+}
+
+#[test]
+fn verifies_sp1_proofs_by_verifier_selector() {
+    let (_, vkey_hash) = PROGRAM_VKEY_HASHES[0];
+    for (kind, mode) in [
+        (SP1ProofKind::Groth16, ProofMode::Groth16),
+        (SP1ProofKind::Plonk, ProofMode::Plonk),
+    ] {
+        let bundle = bundle("fibonacci", kind);
+        let result = verify_sp1(&bundle.raw_proof, &bundle.public_values, vkey_hash, mode);
+        assert_eq!(result.status(), "valid", "{:?}", kind);
+        assert_eq!(result.public_values(), Some(bundle.public_values.clone()));
+
+        let result = verify_sp1_onchain(&bundle.bytes(), &bundle.public_values, vkey_hash, mode);
+        assert_eq!(result.status(), "valid", "{:?}", kind);
+        assert_eq!(result.public_values(), Some(bundle.public_values.clone()));
+
+        let mut proof = bundle.bytes();
+        proof[3] ^= 1;
+        let result = verify_sp1_onchain(&proof, &bundle.public_values, vkey_hash, mode);
+        assert_eq!(result.status(), "malformed", "{:?}", kind);
+
+        assert_eq!(
+            verify_json(&json("fibonacci", kind), mode).status(),
+            "valid",
+            "{:?}",
+            kind
+        );
+    }
+}
+
+#[test]
+fn verifies_proofs_against_runtime_keys() {
+    for (kind, verify) in [
+        (
+            SP1ProofKind::Groth16,
+            verify_groth16_proof_with_inputs as fn(&[u8], &[u8], &[&str]) -> _,
+        ),
+        (SP1ProofKind::Plonk, verify_plonk_proof_with_inputs),
+    ] {
+        let bundle = bundle("fibonacci", kind);
+        let vk = read(&format!("vk/v2.0.0/{}_vk.bin", kind_name(kind)));
+        let json: serde_json::Value = serde_json::from_str(&json("fibonacci", kind)).unwrap();
+        let public_inputs: Vec<&str> = json["public_inputs"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|input| input.as_str().unwrap())
+            .collect();
+
+        let result = verify(&bundle.raw_proof, &vk, &public_inputs);
+        assert_eq!(result.status(), "valid", "{:?}", kind);
+        assert_eq!(result.public_values(), None);
+
+        let result = verify(&bundle.raw_proof, &vk, &[public_inputs[0], "1"]);
+        assert_eq!(result.status(), "invalid", "{:?}", kind);
+
+        let result = verify(&bundle.raw_proof, &vk, &[public_inputs[0], "0xzz"]);
+        assert_eq!(result.status(), "malformed", "{:?}", kind);
+        assert!(result.message().unwrap().contains("public input 1"));
+
+        let result = verify(&bundle.raw_proof, &vk[..vk.len() / 2], &public_inputs);
+        assert_eq!(result.status(), "malformed", "{:?}", kind);
+    }
+}
@@ -18,7 +18,9 @@ fn main() {
     
     let home_dir = std::env::var("HOME").expect("HOME environment variable not set");
     let source_dir = PathBuf::from(home_dir).join(format!(".sp1/circuits/{}", SP1_CIRCUIT_VERSION));
-    let dest_dir = PathBuf::from("../../vk");
+    // One directory per circuit version, see SP1_CIRCUIT_KEYS in verifier/src/sp1/registry.rs
+    let dest_dir = PathBuf::from("../../vk").join(SP1_CIRCUIT_VERSION);
+    fs::create_dir_all(&dest_dir).expect("Failed to create the verification key directory");
 
     let all_files_exist = files_to_copy.iter().all(|file| source_dir.join(file).exists());
     if !all_files_exist {